@@ -1,15 +1,26 @@
 pub mod solution {
-    #[tracing::instrument(skip(input))]
-    pub fn part_a(input: &str) -> anyhow::Result<String> {
-        let nums: Vec<isize> = input
+    use parse::derive::AocParse;
+
+    #[derive(AocParse)]
+    #[aoc(fmt = "{dir}{distance}")]
+    struct Rotation {
+        dir: char,
+        distance: isize,
+    }
+
+    fn rotations(input: &str) -> anyhow::Result<Vec<isize>> {
+        input
             .lines()
             .map(|l| {
-                let (dir, num) = l.split_at(1);
-                let sign = if dir == "L" { -1 } else { 1 };
-                let num: isize = num.parse().expect("Valid num");
-                num * sign
+                let Rotation { dir, distance } = l.parse()?;
+                Ok(if dir == 'L' { -distance } else { distance })
             })
-            .collect();
+            .collect()
+    }
+
+    #[tracing::instrument(skip(input))]
+    pub fn part_a(input: &str) -> anyhow::Result<String> {
+        let nums = rotations(input)?;
         let mut dial = 50;
         let mut res = 0;
         for num in nums {
@@ -24,15 +35,7 @@ pub mod solution {
 
     #[tracing::instrument(skip(input))]
     pub fn part_b(input: &str) -> anyhow::Result<String> {
-        let nums: Vec<isize> = input
-            .lines()
-            .map(|l| {
-                let (dir, num) = l.split_at(1);
-                let sign = if dir == "L" { -1 } else { 1 };
-                let num: isize = num.parse().expect("Valid num");
-                num * sign
-            })
-            .collect();
+        let nums = rotations(input)?;
 
         let mut dial = 50;
         let mut res = 0;
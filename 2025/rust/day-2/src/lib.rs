@@ -1,5 +1,5 @@
 pub mod solution {
-    use parse::range::parse_inclusive_range;
+    use parse::fast;
 
     use crate::solution;
 
@@ -8,7 +8,8 @@ pub mod solution {
         let res: usize = input
             .split(",")
             .map(|text| {
-                let range = parse_inclusive_range::<usize>(text.trim_end()).expect("valid range");
+                let (from, to) = fast::pair::<usize>(text.as_bytes()).expect("valid range");
+                let range = from..=to;
                 range
                     .filter(|num| {
                         let str = num.to_string();
@@ -27,7 +28,8 @@ pub mod solution {
         let res: usize = input
             .split(",")
             .map(|text| {
-                let range = parse_inclusive_range::<usize>(text.trim_end()).expect("valid range");
+                let (from, to) = fast::pair::<usize>(text.as_bytes()).expect("valid range");
+                let range = from..=to;
                 range
                     .filter(|num| solution::is_invalid_b(*num))
                     .sum::<usize>()
@@ -1,6 +1,4 @@
 pub mod solution {
-    use std::collections::HashSet;
-
     pub use grid::prelude::*;
 
     #[tracing::instrument(skip(input))]
@@ -20,33 +18,17 @@ pub mod solution {
 
     #[tracing::instrument(skip(input))]
     pub fn part_b(input: &str) -> anyhow::Result<String> {
-        let init_grid = GridBuilder::<()>::build_obstacle_grid()
+        let grid = GridBuilder::<()>::build_obstacle_grid()
             .input(input)
-            .obstacle('@')
+            .obstacle('.')
             .call()?
             .grid;
-        let size = init_grid.size();
-        let mut obstacles: HashSet<_> = init_grid.walkable_tiles().keys().cloned().collect();
-        let mut removed = 0;
-
-        loop {
-            let grid = Grid::<()>::from_obstacles(obstacles.clone(), size);
-            // grid.print_debug_map(|_| Some('.'));
-            let to_remove: Vec<_> = grid
-                .walkable_tiles()
-                .keys()
-                .filter(|t| grid.neighbours_8(**t).len() < 4)
-                .collect();
-
-            if to_remove.len() == 0 {
-                break;
-            }
-
-            removed += to_remove.len();
-            obstacles.extend(to_remove);
-        }
+        let mut rolls =
+            Automaton::from_grid(&grid, |_, roll| roll.is_some()).with_incremental(true);
+        let initial = rolls.live_count();
+        rolls.run(|roll, neighbours| roll && neighbours >= 4, usize::MAX);
 
-        Ok(removed.to_string())
+        Ok((initial - rolls.live_count()).to_string())
     }
 }
 
@@ -1,46 +1,21 @@
 pub mod solution {
-    use parse::range::parse_inclusive_range;
+    use parse::sections::{Numbers, Ranges, parse_sections};
     use range::merge_all_bounded_ranges;
 
     #[tracing::instrument(skip(input))]
     pub fn part_a(input: &str) -> anyhow::Result<String> {
-        let var_name = true;
-        let mut parsing_ranges = var_name;
-        let mut ranges = Vec::with_capacity(1000);
-        // let mut ids = Vec::with_capacity(1000);
-        let mut res = 0;
-        for l in input.lines() {
-            if l.is_empty() {
-                parsing_ranges = false;
-                continue;
-            }
-
-            if parsing_ranges {
-                ranges.push(parse_inclusive_range(l)?);
-            } else {
-                let num = l.parse::<usize>().expect("is num");
-                // ids.push();
-                if ranges.iter().any(|r| r.contains(&num)) {
-                    res += 1;
-                }
-            }
-        }
+        let (Ranges(ranges), Numbers(ids)): (_, Numbers<usize>) = parse_sections(input)?;
+        let res = ids
+            .iter()
+            .filter(|id| ranges.iter().any(|r| r.contains(*id)))
+            .count();
 
         Ok(res.to_string())
     }
 
     #[tracing::instrument(skip(input))]
     pub fn part_b(input: &str) -> anyhow::Result<String> {
-        let mut ranges: Vec<_> = input
-            .lines()
-            .map_while(|l| {
-                if l.is_empty() {
-                    return None;
-                }
-
-                Some(parse_inclusive_range::<usize>(l).expect("valid range"))
-            })
-            .collect();
+        let (Ranges::<usize>(mut ranges), Numbers::<usize>(_)) = parse_sections(input)?;
         ranges.sort_by_key(|r| *r.start());
         merge_all_bounded_ranges(&mut ranges);
 
@@ -1,5 +1,9 @@
 pub mod solution {
     use anyhow::Context;
+    use parse::{
+        columns::{ReadOrder, TextBlock},
+        fast::integers,
+    };
 
     #[derive(Debug)]
     enum ColumnOp {
@@ -19,8 +23,7 @@ pub mod solution {
             })
             .collect();
         for l in lines.into_iter().rev().skip(1) {
-            for (i, num) in l.split_whitespace().enumerate() {
-                let num: usize = num.parse()?;
+            for (i, num) in integers::<usize>(l.as_bytes()).enumerate() {
                 let current = col_results[i];
                 col_results[i] = match ops[i] {
                     ColumnOp::Add => current + num,
@@ -45,57 +48,30 @@ pub mod solution {
 
     #[tracing::instrument(skip(input))]
     pub fn part_b(input: &str) -> anyhow::Result<String> {
-        let lines: Vec<_> = input.lines().collect();
-        let ops_line = lines.last().context("ops line exists")?;
-        let width = ops_line.len();
-        let ops = parse_ops_b(&ops_line);
-        let chars: Vec<Vec<_>> = lines
-            .iter()
-            .rev()
-            .skip(1)
-            .map(|l| l.chars().collect())
-            .collect();
+        let block = TextBlock::new(input);
+        let ops_y = block.height().checked_sub(1).context("ops line exists")?;
+        let numbers = block.sub_block(0..ops_y);
         let mut res = 0;
-        for (i, (x_from, op)) in ops.iter().enumerate() {
-            let mut col_sum: usize = match op {
+        for columns in block.marker_columns(ops_y, |b| b == b'+' || b == b'*') {
+            let op = match block.byte(columns.start, ops_y) {
+                b'+' => ColumnOp::Add,
+                _ => ColumnOp::Mul,
+            };
+            let mut col_res: usize = match op {
                 ColumnOp::Add => 0,
                 ColumnOp::Mul => 1,
             };
-            let x_to = ops.get(i + 1).map_or_else(|| width, |(i, _)| i - 1);
-            for x in *x_from..x_to {
-                let mut mult = 1;
-                let mut num = 0;
-                for line in &chars {
-                    let c = line[x];
-                    match c.to_digit(10) {
-                        Some(n) => {
-                            num += n as usize * mult;
-                            mult *= 10;
-                        }
-                        None if mult > 1 => break,
-                        _ => {}
-                    }
-                }
+            for digits in numbers.vertical_digits(columns, ReadOrder::TopToBottom) {
+                let num: usize = digits.parse()?;
                 match op {
-                    ColumnOp::Add => col_sum += num,
-                    ColumnOp::Mul => col_sum *= num,
-                };
+                    ColumnOp::Add => col_res += num,
+                    ColumnOp::Mul => col_res *= num,
+                }
             }
-            res += col_sum;
+            res += col_res;
         }
         Ok(res.to_string())
     }
-
-    fn parse_ops_b(line: &str) -> Vec<(usize, ColumnOp)> {
-        line.chars()
-            .enumerate()
-            .filter_map(|(i, c)| match c {
-                '+' => Some((i, ColumnOp::Add)),
-                '*' => Some((i, ColumnOp::Mul)),
-                _ => None,
-            })
-            .collect()
-    }
 }
 
 #[cfg(test)]
@@ -2,23 +2,20 @@ pub mod solution {
     use std::collections::{HashMap, HashSet};
 
     use anyhow::Context;
+    use grid::prelude::*;
 
     #[tracing::instrument(skip(input))]
     pub fn part_a(input: &str) -> anyhow::Result<String> {
-        let lines: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
-        let start_line = &lines[0];
-        let width = start_line.len();
-        let start_x = start_line
-            .iter()
-            .position(|c| *c == 'S')
-            .context("start tile")?;
+        let grid = ByteGrid::new(input)?;
+        let width = grid.size().x as usize;
+        let start_x = grid.find(b'S').context("start tile")?.x as usize;
         let mut beams = HashSet::with_capacity(width);
         beams.insert(start_x);
         let mut line_beams = Vec::with_capacity(width);
         let mut split_count = 0;
-        for l in lines.iter().skip(1) {
+        for l in grid.rows().skip(1) {
             for x in &beams {
-                if l[*x] == '^' {
+                if l[*x] == b'^' {
                     split_count += 1;
                     line_beams.push(x - 1);
                     line_beams.push(x + 1);
@@ -34,20 +31,16 @@ pub mod solution {
 
     #[tracing::instrument(skip(input))]
     pub fn part_b(input: &str) -> anyhow::Result<String> {
-        let lines: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
-        let start_line = &lines[0];
-        let start_x = start_line
-            .iter()
-            .position(|c| *c == 'S')
-            .context("start tile")?;
+        let grid = ByteGrid::new(input)?;
+        let start_x = grid.find(b'S').context("start tile")?.x as usize;
 
-        let split_counts = lines.iter().enumerate().skip(2).fold(
+        let split_counts = grid.rows().enumerate().skip(2).fold(
             HashMap::from([(start_x, 1)]),
             |counts, (_, l)| {
                 let mut new_counts = HashMap::new();
                 for (x, curr_count) in counts {
                     match l[x] {
-                        '^' => {
+                        b'^' => {
                             new_counts
                                 .entry(x - 1)
                                 .and_modify(|c| *c += curr_count)
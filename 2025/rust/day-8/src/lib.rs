@@ -1,6 +1,7 @@
 pub mod solution {
     use glam::IVec3;
     use itertools::Itertools;
+    use parse::vec::parse_ivec3_res;
     use std::collections::HashSet;
 
     #[derive(Debug, Clone, PartialEq)]
@@ -53,7 +54,7 @@ pub mod solution {
         input: &str,
         connection_count: usize,
     ) -> anyhow::Result<String> {
-        let coords = parse_coords(input);
+        let coords = parse_coords(input)?;
         let mut distances = distances(&coords);
         let mut circuits: Vec<HashSet<usize>> = Vec::with_capacity(connection_count);
         for _ in 0..connection_count {
@@ -68,7 +69,7 @@ pub mod solution {
 
     #[tracing::instrument(skip(input))]
     pub fn part_b(input: &str) -> anyhow::Result<String> {
-        let coords = parse_coords(input);
+        let coords = parse_coords(input)?;
         let mut distances = distances(&coords);
         let mut circuits: Vec<HashSet<usize>> = Vec::with_capacity(1000);
         loop {
@@ -82,15 +83,8 @@ pub mod solution {
         }
     }
 
-    fn parse_coords(input: &str) -> Vec<IVec3> {
-        input
-            .lines()
-            .map(|l| {
-                let (x, rest) = l.split_once(',').unwrap();
-                let (y, z) = rest.split_once(',').unwrap();
-                IVec3::new(x.parse().unwrap(), y.parse().unwrap(), z.parse().unwrap())
-            })
-            .collect()
+    fn parse_coords(input: &str) -> anyhow::Result<Vec<IVec3>> {
+        input.lines().map(parse_ivec3_res).collect()
     }
 
     fn distances(coords: &[IVec3]) -> Vec<DistIndex> {
@@ -1,5 +1,6 @@
 pub mod solution {
     use glam::U64Vec2;
+    use grid::compress::CompressedPolygon;
     use itertools::Itertools;
     use parse::vec::parse_u64vec2_res;
 
@@ -30,33 +31,11 @@ pub mod solution {
     }
 
     pub(super) fn max_area_b(tiles: &[U64Vec2]) -> u64 {
-        let lines: Vec<(_, _)> = tiles.iter().circular_tuple_windows().collect();
+        let polygon = CompressedPolygon::new(tiles);
         tiles
             .iter()
             .tuple_combinations()
-            .filter(|(a, b)| {
-                let top_left = a.min(**b);
-                let btm_right = a.max(**b);
-                // no lines crossing the rectangle
-                !lines.iter().any(|(a, b)| {
-                    let horizontal = a.y == b.y;
-                    if horizontal {
-                        a.y > top_left.y
-                            && a.y < btm_right.y
-                            && (a.x > top_left.x && a.x < btm_right.x
-                                || b.x > top_left.x && b.x < btm_right.x
-                                || a.x <= top_left.x && b.x >= btm_right.x
-                                || b.x <= top_left.x && a.x >= btm_right.x)
-                    } else {
-                        a.x > top_left.x
-                            && a.x < btm_right.x
-                            && (a.y > top_left.y && a.y < btm_right.y
-                                || b.y > top_left.y && b.y < btm_right.y
-                                || a.y <= top_left.y && b.y >= btm_right.y
-                                || b.y <= top_left.y && a.y >= btm_right.y)
-                    }
-                })
-            })
+            .filter(|(a, b)| polygon.contains_rect(**a, **b))
             .map(|(a, b)| (a.max(*b) - a.min(*b) + U64Vec2::ONE).element_product())
             .max()
             .unwrap()
@@ -95,7 +74,9 @@ mod tests {
     #[test_case(vec![(0, 0), (2, 0), (2, 1), (0, 1)] => 6; "2x1 rect")]
     #[test_case(vec![(0, 0), (2, 0), (2, 1), (1, 1), (1, 2), (0, 2)] => 6; "2-long L")]
     #[test_case(vec![(0, 0), (3, 0), (3, 1), (1, 1), (1, 2), (0, 2)] => 8; "3-long L")]
-    #[test_case(vec![(0, 0), (3, 0), (3, 2), (2, 2), (2, 1), (1, 1), (1, 2), (0, 2)] => 6; "bridge")]
+    // The notch between x = 1 and x = 2 has no width, so every tile of the 4x3 box is on the
+    // boundary and the whole box counts.
+    #[test_case(vec![(0, 0), (3, 0), (3, 2), (2, 2), (2, 1), (1, 1), (1, 2), (0, 2)] => 12; "bridge")]
     #[test_case(vec![(7, 1), (11, 1), (11, 7), (9, 7), (9, 5), (2, 5), (2, 3), (7, 3)] => 24; "test input")]
     #[traced_test]
     fn day_area(tiles: Vec<(u64, u64)>) -> u64 {
@@ -0,0 +1,217 @@
+use glam::{U64Vec2, UVec2};
+use std::collections::VecDeque;
+
+use crate::{dir::DIRS_4, grid::SummedAreaTable, iter::grid_iter};
+
+/// Maps large sparse tile coordinates onto a compact grid of cells.
+///
+/// Every used coordinate gets a cell of its own, the gaps between them are collapsed
+/// into single cells spanning the whole gap.
+#[derive(Debug, Clone)]
+pub struct CoordCompression {
+    xs: Vec<u64>,
+    ys: Vec<u64>,
+}
+impl CoordCompression {
+    pub fn new(points: impl IntoIterator<Item = U64Vec2>) -> Self {
+        let (mut xs, mut ys): (Vec<_>, Vec<_>) = points
+            .into_iter()
+            .flat_map(|p| [p, p + U64Vec2::ONE])
+            .map(|p| (p.x, p.y))
+            .unzip();
+        xs.sort_unstable();
+        xs.dedup();
+        ys.sort_unstable();
+        ys.dedup();
+        Self { xs, ys }
+    }
+
+    /// Number of cells along each axis.
+    #[must_use]
+    pub fn size(&self) -> UVec2 {
+        UVec2::new(
+            self.xs.len().saturating_sub(1) as _,
+            self.ys.len().saturating_sub(1) as _,
+        )
+    }
+
+    /// Cell of a point used to build the compression.
+    #[must_use]
+    pub fn compress(&self, point: U64Vec2) -> Option<UVec2> {
+        let x = self.xs.binary_search(&point.x).ok()?;
+        let y = self.ys.binary_search(&point.y).ok()?;
+        let cell = UVec2::new(x as _, y as _);
+        cell.cmplt(self.size()).all().then_some(cell)
+    }
+
+    /// Original coordinates of the top-left tile of the cell.
+    #[must_use]
+    pub fn expand(&self, cell: UVec2) -> U64Vec2 {
+        U64Vec2::new(self.xs[cell.x as usize], self.ys[cell.y as usize])
+    }
+
+    /// Width and height of the cell in original tiles.
+    #[must_use]
+    pub fn cell_extent(&self, cell: UVec2) -> U64Vec2 {
+        self.expand(cell + UVec2::ONE) - self.expand(cell)
+    }
+}
+
+/// Rectilinear polygon over compressed coordinates with its interior filled in.
+///
+/// Vertices are tiles and the boundary between consecutive vertices is part of the polygon.
+#[derive(Debug, Clone)]
+pub struct CompressedPolygon {
+    compression: CoordCompression,
+    inside: Vec<bool>,
+    /// Original area of the inside cells.
+    areas: SummedAreaTable<u64>,
+}
+impl CompressedPolygon {
+    /// Vertices in boundary order, consecutive vertices must share a row or a column.
+    #[must_use]
+    pub fn new(vertices: &[U64Vec2]) -> Self {
+        let compression = CoordCompression::new(vertices.iter().copied());
+        let size = compression.size();
+        let width = size.x as usize;
+        let mut inside = vec![true; size.element_product() as usize];
+
+        let mut boundary = vec![false; inside.len()];
+        for (a, b) in vertices.iter().zip(vertices.iter().cycle().skip(1)) {
+            debug_assert!(a.x == b.x || a.y == b.y, "diagonal edge {a} - {b}");
+            let (Some(a), Some(b)) = (compression.compress(*a), compression.compress(*b)) else {
+                continue;
+            };
+            for y in a.y.min(b.y)..=a.y.max(b.y) {
+                for x in a.x.min(b.x)..=a.x.max(b.x) {
+                    boundary[y as usize * width + x as usize] = true;
+                }
+            }
+        }
+
+        // The outside is everything reachable from the border without crossing the boundary.
+        let mut q: VecDeque<_> = grid_iter(size)
+            .filter(|c| c.x == 0 || c.y == 0 || c.x == size.x - 1 || c.y == size.y - 1)
+            .collect();
+        while let Some(cell) = q.pop_front() {
+            let i = cell.y as usize * width + cell.x as usize;
+            if boundary[i] || !inside[i] {
+                continue;
+            }
+            inside[i] = false;
+            for dir in DIRS_4 {
+                let next = cell.as_ivec2() + dir;
+                if next.min_element() >= 0 && next.as_uvec2().cmplt(size).all() {
+                    q.push_back(next.as_uvec2());
+                }
+            }
+        }
+
+        let areas = SummedAreaTable::new(size, |cell| {
+            if inside[cell.y as usize * width + cell.x as usize] {
+                compression.cell_extent(cell).element_product()
+            } else {
+                0
+            }
+        });
+        Self {
+            compression,
+            inside,
+            areas,
+        }
+    }
+
+    #[must_use]
+    pub fn compression(&self) -> &CoordCompression {
+        &self.compression
+    }
+
+    /// Whether the tile lies inside or on the boundary.
+    #[must_use]
+    pub fn contains(&self, tile: U64Vec2) -> bool {
+        let c = &self.compression;
+        let x = c.xs.partition_point(|x| *x <= tile.x);
+        let y = c.ys.partition_point(|y| *y <= tile.y);
+        if x == 0 || y == 0 || x >= c.xs.len() || y >= c.ys.len() {
+            return false;
+        }
+        self.inside[(y - 1) * (c.xs.len() - 1) + x - 1]
+    }
+
+    /// Tiles inside or on the boundary.
+    #[must_use]
+    pub fn area(&self) -> u64 {
+        self.areas.total()
+    }
+
+    /// Tiles of the polygon within the rectangle spanned by two vertices (inclusive).
+    #[must_use]
+    pub fn filled_area(&self, a: U64Vec2, b: U64Vec2) -> Option<u64> {
+        let min = self.compression.compress(a.min(b))?;
+        let max = self.compression.compress(a.max(b))?;
+        Some(self.areas.sum(min, max))
+    }
+
+    /// Whether the rectangle spanned by two vertices lies fully within the polygon.
+    #[must_use]
+    pub fn contains_rect(&self, a: U64Vec2, b: U64Vec2) -> bool {
+        let rect_area = (a.max(b) - a.min(b) + U64Vec2::ONE).element_product();
+        self.filled_area(a, b) == Some(rect_area)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    fn polygon(vertices: &[(u64, u64)]) -> CompressedPolygon {
+        let vertices: Vec<_> = vertices.iter().map(|(x, y)| U64Vec2::new(*x, *y)).collect();
+        CompressedPolygon::new(&vertices)
+    }
+
+    const EXAMPLE: [(u64, u64); 8] = [
+        (7, 1),
+        (11, 1),
+        (11, 7),
+        (9, 7),
+        (9, 5),
+        (2, 5),
+        (2, 3),
+        (7, 3),
+    ];
+
+    #[test]
+    #[traced_test]
+    fn compression() {
+        let c = CoordCompression::new([U64Vec2::new(10, 1_000_000), U64Vec2::new(20, 5)]);
+        assert_eq!(c.size(), UVec2::new(3, 3));
+        assert_eq!(
+            c.compress(U64Vec2::new(20, 1_000_000)),
+            Some(UVec2::new(2, 2))
+        );
+        assert_eq!(c.compress(U64Vec2::new(15, 5)), None);
+        assert_eq!(c.expand(UVec2::new(1, 1)), U64Vec2::new(11, 6));
+        assert_eq!(c.cell_extent(UVec2::new(1, 1)), U64Vec2::new(9, 999_994));
+    }
+
+    #[test]
+    #[traced_test]
+    fn fill() {
+        let polygon = polygon(&EXAMPLE);
+        assert_eq!(polygon.area(), 5 * 2 + 10 * 3 + 3 * 2);
+        assert!(polygon.contains(U64Vec2::new(10, 6)));
+        assert!(polygon.contains(U64Vec2::new(3, 4)));
+        assert!(!polygon.contains(U64Vec2::new(3, 2)));
+        assert!(!polygon.contains(U64Vec2::new(12, 2)));
+    }
+
+    #[test_case((9, 5), (2, 3) => true)]
+    #[test_case((7, 1), (11, 7) => false)]
+    #[test_case((2, 5), (11, 1) => false)]
+    #[traced_test]
+    fn contains_rect(a: (u64, u64), b: (u64, u64)) -> bool {
+        polygon(&EXAMPLE).contains_rect(a.into(), b.into())
+    }
+}
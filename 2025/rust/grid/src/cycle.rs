@@ -0,0 +1,202 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Repeating part of a sequence `x0, step(x0), step(step(x0)), ...`.
+///
+/// The state at index `start` is the first one that is seen again, `len` steps later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub start: usize,
+    pub len: usize,
+}
+impl Cycle {
+    /// Earliest index whose state equals the state at step `n`.
+    #[must_use]
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.len
+        }
+    }
+}
+
+/// Floyd's tortoise and hare, constant memory but roughly three `step` calls per state.
+pub fn find_cycle_floyd<S: PartialEq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut len = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        len += 1;
+    }
+    Cycle { start, len }
+}
+
+/// Brent's algorithm, constant memory and fewer `step` calls than [`find_cycle_floyd`].
+pub fn find_cycle_brent<S: PartialEq + Clone>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut len = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+        hare = step(&hare);
+        len += 1;
+    }
+
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..len {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, len }
+}
+
+/// Remembers the `fingerprint` of every visited state, `None` when `step` stops before a
+/// state repeats.
+///
+/// The fingerprint only has to capture what determines the following states, e.g. a guard's
+/// position and direction but not the set of tiles it visited so far.
+pub fn find_cycle_hashed<S, K: Hash + Eq>(
+    initial: S,
+    mut step: impl FnMut(&S) -> Option<S>,
+    mut fingerprint: impl FnMut(&S) -> K,
+) -> Option<Cycle> {
+    let mut seen = HashMap::new();
+    let mut state = initial;
+    let mut i = 0;
+    loop {
+        if let Some(start) = seen.insert(fingerprint(&state), i) {
+            return Some(Cycle {
+                start,
+                len: i - start,
+            });
+        }
+        state = step(&state)?;
+        i += 1;
+    }
+}
+
+/// State after `n` steps, simulating only until the first repeated fingerprint and
+/// extrapolating the rest with the detected cycle.
+pub fn skip_to_step<S, K: Hash + Eq>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    mut fingerprint: impl FnMut(&S) -> K,
+    n: usize,
+) -> S {
+    let mut seen = HashMap::new();
+    let mut states = vec![initial];
+    while states.len() <= n {
+        let i = states.len() - 1;
+        if let Some(start) = seen.insert(fingerprint(&states[i]), i) {
+            let cycle = Cycle {
+                start,
+                len: i - start,
+            };
+            return states.swap_remove(cycle.equivalent_step(n));
+        }
+        let next = step(&states[i]);
+        states.push(next);
+    }
+    states.swap_remove(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dir::Dir4, grid::builder::GridBuilder};
+    use glam::UVec2;
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    fn step(x: &u32) -> u32 {
+        (x * x + 1) % 255
+    }
+
+    #[test]
+    #[traced_test]
+    fn algorithms_agree() {
+        let floyd = find_cycle_floyd(3, step);
+        assert_eq!(find_cycle_brent(3, step), floyd);
+        assert_eq!(find_cycle_hashed(3, |x| Some(step(x)), |x| *x), Some(floyd));
+
+        let mut x = 3;
+        for _ in 0..floyd.start {
+            x = step(&x);
+        }
+        let cycle_start = x;
+        for _ in 0..floyd.len {
+            x = step(&x);
+        }
+        assert_eq!(x, cycle_start);
+    }
+
+    #[test_case(3)]
+    #[test_case(1_000_000_000)]
+    #[traced_test]
+    fn skip(n: usize) {
+        let counter = |i: &usize| i + 1;
+        let skipped = skip_to_step(0, counter, |i| i % 7, n);
+        assert_eq!(skipped % 7, n % 7);
+        assert!(skipped < 14);
+    }
+
+    const GUARD: &str = "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+
+    #[test_case(None => false)]
+    #[test_case(Some(UVec2::new(3, 6)) => true)]
+    #[test_case(Some(UVec2::new(7, 9)) => true)]
+    #[test_case(Some(UVec2::new(0, 0)) => false)]
+    #[traced_test]
+    fn guard_loop(extra_obstacle: Option<UVec2>) -> bool {
+        let built = GridBuilder::<()>::build_obstacle_grid()
+            .input(GUARD)
+            .obstacle('#')
+            .start_character('^')
+            .call()
+            .unwrap();
+        let grid = built.grid;
+        let walk = |(pos, dir): &(UVec2, Dir4)| match grid.move_target(*pos, *dir) {
+            Some((target, ())) if Some(target) != extra_obstacle => Some((target, *dir)),
+            _ if grid.move_within_bounds(*pos, *dir) => Some((*pos, dir.rotate_cw())),
+            _ => None,
+        };
+        find_cycle_hashed((built.start_tile.unwrap(), Dir4::North), walk, |s| *s).is_some()
+    }
+}
@@ -1,4 +1,6 @@
-use glam::IVec2;
+use derive_more::derive::{Display, Error};
+use glam::{IVec2, IVec3};
+use std::str::FromStr;
 
 pub const DIRS_4: [IVec2; 4] = [IVec2::NEG_Y, IVec2::X, IVec2::Y, IVec2::NEG_X];
 pub const DIRS_8: [IVec2; 8] = [
@@ -11,3 +13,309 @@ pub const DIRS_8: [IVec2; 8] = [
     IVec2::NEG_X,
     IVec2::NEG_ONE,
 ];
+pub const DIRS_6: [IVec3; 6] = [
+    IVec3::NEG_X,
+    IVec3::X,
+    IVec3::NEG_Y,
+    IVec3::Y,
+    IVec3::NEG_Z,
+    IVec3::Z,
+];
+/// Every offset in a 3x3x3 cube except the center.
+pub const DIRS_26: [IVec3; 26] = dirs_26();
+
+const fn dirs_26() -> [IVec3; 26] {
+    let mut dirs = [IVec3::ZERO; 26];
+    let mut i = 0;
+    let mut n = 0;
+    while n < 27 {
+        if n != 13 {
+            dirs[i] = IVec3::new(n % 3 - 1, n / 3 % 3 - 1, n / 9 - 1);
+            i += 1;
+        }
+        n += 1;
+    }
+    dirs
+}
+
+#[derive(Debug, Error, Display, PartialEq, Eq)]
+pub enum ParseDirError {
+    #[display("Invalid direction char: {_0:?}")]
+    InvalidChar(#[error(not(source))] char),
+    #[display("Invalid direction: {_0:?}")]
+    InvalidStr(#[error(not(source))] String),
+    #[display("Not a direction vector: {_0}")]
+    InvalidVector(#[error(not(source))] IVec2),
+}
+
+/// Cardinal direction in y-down coordinates (north is `-Y`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dir4 {
+    North,
+    East,
+    South,
+    West,
+}
+impl Dir4 {
+    /// Clockwise from north, same order as [`DIRS_4`].
+    pub const ALL: [Dir4; 4] = [Dir4::North, Dir4::East, Dir4::South, Dir4::West];
+
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Self::ALL.into_iter()
+    }
+
+    #[must_use]
+    pub const fn rotate_cw(self) -> Self {
+        match self {
+            Dir4::North => Dir4::East,
+            Dir4::East => Dir4::South,
+            Dir4::South => Dir4::West,
+            Dir4::West => Dir4::North,
+        }
+    }
+
+    #[must_use]
+    pub const fn rotate_ccw(self) -> Self {
+        match self {
+            Dir4::North => Dir4::West,
+            Dir4::East => Dir4::North,
+            Dir4::South => Dir4::East,
+            Dir4::West => Dir4::South,
+        }
+    }
+
+    #[must_use]
+    pub const fn opposite(self) -> Self {
+        match self {
+            Dir4::North => Dir4::South,
+            Dir4::East => Dir4::West,
+            Dir4::South => Dir4::North,
+            Dir4::West => Dir4::East,
+        }
+    }
+
+    #[must_use]
+    pub const fn to_ivec2(self) -> IVec2 {
+        DIRS_4[self as usize]
+    }
+
+    #[must_use]
+    pub const fn is_horizontal(self) -> bool {
+        matches!(self, Dir4::East | Dir4::West)
+    }
+
+    #[must_use]
+    pub const fn is_vertical(self) -> bool {
+        !self.is_horizontal()
+    }
+
+    #[must_use]
+    pub const fn to_arrow(self) -> char {
+        match self {
+            Dir4::North => '^',
+            Dir4::East => '>',
+            Dir4::South => 'v',
+            Dir4::West => '<',
+        }
+    }
+
+    /// Parses arrows (`^>v<`), compass points (`NESW`) and `UDLR` in either case.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for any other char.
+    pub fn from_char(c: char) -> Result<Self, ParseDirError> {
+        match c {
+            '^' | 'N' | 'n' | 'U' | 'u' => Ok(Dir4::North),
+            '>' | 'E' | 'e' | 'R' | 'r' => Ok(Dir4::East),
+            'v' | 'V' | 'S' | 's' | 'D' | 'd' => Ok(Dir4::South),
+            '<' | 'W' | 'w' | 'L' | 'l' => Ok(Dir4::West),
+            c => Err(ParseDirError::InvalidChar(c)),
+        }
+    }
+}
+impl From<Dir4> for IVec2 {
+    fn from(dir: Dir4) -> Self {
+        dir.to_ivec2()
+    }
+}
+impl TryFrom<IVec2> for Dir4 {
+    type Error = ParseDirError;
+
+    fn try_from(vec: IVec2) -> Result<Self, Self::Error> {
+        Self::iter()
+            .find(|d| d.to_ivec2() == vec)
+            .ok_or(ParseDirError::InvalidVector(vec))
+    }
+}
+impl TryFrom<char> for Dir4 {
+    type Error = ParseDirError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        Self::from_char(c)
+    }
+}
+impl FromStr for Dir4 {
+    type Err = ParseDirError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Self::from_char(c),
+            _ => Err(ParseDirError::InvalidStr(s.to_string())),
+        }
+    }
+}
+
+/// Cardinal and intercardinal direction in y-down coordinates (north is `-Y`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dir8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+impl Dir8 {
+    /// Clockwise from north, same order as [`DIRS_8`].
+    pub const ALL: [Dir8; 8] = [
+        Dir8::North,
+        Dir8::NorthEast,
+        Dir8::East,
+        Dir8::SouthEast,
+        Dir8::South,
+        Dir8::SouthWest,
+        Dir8::West,
+        Dir8::NorthWest,
+    ];
+
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Self::ALL.into_iter()
+    }
+
+    /// Rotates by 45°.
+    #[must_use]
+    pub const fn rotate_cw(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Rotates by 45°.
+    #[must_use]
+    pub const fn rotate_ccw(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    #[must_use]
+    pub const fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    #[must_use]
+    pub const fn to_ivec2(self) -> IVec2 {
+        DIRS_8[self as usize]
+    }
+
+    #[must_use]
+    pub const fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+
+    /// Cardinal part of the direction, `None` for diagonals.
+    #[must_use]
+    pub const fn to_dir4(self) -> Option<Dir4> {
+        match self {
+            Dir8::North => Some(Dir4::North),
+            Dir8::East => Some(Dir4::East),
+            Dir8::South => Some(Dir4::South),
+            Dir8::West => Some(Dir4::West),
+            _ => None,
+        }
+    }
+}
+impl From<Dir8> for IVec2 {
+    fn from(dir: Dir8) -> Self {
+        dir.to_ivec2()
+    }
+}
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Self {
+        Self::ALL[dir as usize * 2]
+    }
+}
+impl TryFrom<IVec2> for Dir8 {
+    type Error = ParseDirError;
+
+    fn try_from(vec: IVec2) -> Result<Self, Self::Error> {
+        Self::iter()
+            .find(|d| d.to_ivec2() == vec)
+            .ok_or(ParseDirError::InvalidVector(vec))
+    }
+}
+impl FromStr for Dir8 {
+    type Err = ParseDirError;
+
+    /// Parses compass points (`N`, `NE`, ...) in either case or any [`Dir4`] char.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "NE" => Ok(Dir8::NorthEast),
+            "SE" => Ok(Dir8::SouthEast),
+            "SW" => Ok(Dir8::SouthWest),
+            "NW" => Ok(Dir8::NorthWest),
+            _ => s
+                .parse::<Dir4>()
+                .map(Into::into)
+                .map_err(|_| ParseDirError::InvalidStr(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    #[test_case('^' => Ok(Dir4::North))]
+    #[test_case('E' => Ok(Dir4::East))]
+    #[test_case('v' => Ok(Dir4::South))]
+    #[test_case('L' => Ok(Dir4::West))]
+    #[test_case('x' => Err(ParseDirError::InvalidChar('x')))]
+    #[traced_test]
+    fn dir4_from_char(c: char) -> Result<Dir4, ParseDirError> {
+        Dir4::try_from(c)
+    }
+
+    #[test]
+    #[traced_test]
+    fn dir4_rotation() {
+        for dir in Dir4::iter() {
+            assert_eq!(dir.rotate_cw().rotate_ccw(), dir);
+            assert_eq!(dir.rotate_cw().rotate_cw(), dir.opposite());
+            assert_eq!(dir.rotate_cw().to_ivec2(), dir.to_ivec2().perp());
+            assert_eq!(Dir4::try_from(dir.to_ivec2()), Ok(dir));
+        }
+    }
+
+    #[test_case("ne" => Ok(Dir8::NorthEast))]
+    #[test_case("W" => Ok(Dir8::West))]
+    #[test_case("NN" => Err(ParseDirError::InvalidStr("NN".to_string())))]
+    #[traced_test]
+    fn dir8_from_str(s: &str) -> Result<Dir8, ParseDirError> {
+        s.parse()
+    }
+
+    #[test]
+    #[traced_test]
+    fn dir8_rotation() {
+        for dir in Dir8::iter() {
+            assert_eq!(dir.rotate_cw().rotate_ccw(), dir);
+            assert_eq!(dir.opposite().to_ivec2(), -dir.to_ivec2());
+            assert_eq!(Dir8::try_from(dir.to_ivec2()), Ok(dir));
+        }
+        assert_eq!(Dir8::from(Dir4::South), Dir8::South);
+        assert_eq!(Dir8::NorthWest.to_dir4(), None);
+    }
+}
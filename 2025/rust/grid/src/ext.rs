@@ -1,4 +1,4 @@
-use glam::UVec2;
+use glam::{IVec3, UVec2, UVec3};
 
 pub trait UVec2Ext {
     fn manhattan_distance(&self, other: UVec2) -> u32;
@@ -9,6 +9,24 @@ impl UVec2Ext for UVec2 {
     }
 }
 
+pub trait UVec3Ext {
+    fn manhattan_distance(&self, other: UVec3) -> u32;
+}
+impl UVec3Ext for UVec3 {
+    fn manhattan_distance(&self, other: UVec3) -> u32 {
+        (self.as_ivec3() - other.as_ivec3()).abs().element_sum() as _
+    }
+}
+
+pub trait IVec3Ext {
+    fn manhattan_distance(&self, other: IVec3) -> u32;
+}
+impl IVec3Ext for IVec3 {
+    fn manhattan_distance(&self, other: IVec3) -> u32 {
+        (*self - other).abs().element_sum() as _
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -23,4 +41,17 @@ mod tests {
     fn manhattan(a: UVec2, b: UVec2) -> u32 {
         a.manhattan_distance(b)
     }
+
+    #[test_case(IVec3::ZERO, IVec3::new(1, -2, 3) => 6)]
+    #[test_case(IVec3::new(162, 817, 812), IVec3::new(425, 690, 689) => 513)]
+    #[traced_test]
+    fn manhattan_3d(a: IVec3, b: IVec3) -> u32 {
+        a.manhattan_distance(b)
+    }
+
+    #[test]
+    #[traced_test]
+    fn manhattan_3d_unsigned() {
+        assert_eq!(UVec3::new(3, 0, 1).manhattan_distance(UVec3::ONE), 3);
+    }
 }
@@ -0,0 +1,471 @@
+use glam::{IVec2, UVec2};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::{
+    hash::{BuildHasher, BuildHasherDefault, DefaultHasher, Hash},
+    iter::once,
+};
+
+use crate::{
+    cycle::Cycle,
+    dir::{DIRS_4, DIRS_8},
+    iter::grid_iter,
+};
+
+use super::{Grid, Topology};
+
+/// Cells whose state is counted by an [`Automaton`] rule.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Neighbourhood {
+    /// The 4 orthogonal neighbours.
+    VonNeumann,
+    /// The 8 orthogonal and diagonal neighbours.
+    #[default]
+    Moore,
+}
+impl Neighbourhood {
+    #[must_use]
+    pub fn dirs(self) -> &'static [IVec2] {
+        match self {
+            Neighbourhood::VonNeumann => &DIRS_4,
+            Neighbourhood::Moore => &DIRS_8,
+        }
+    }
+}
+
+/// Why [`Automaton::run`] stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// A step changed nothing, `generation` is the first one of the stable state.
+    Fixpoint { generation: usize },
+    /// The state of generation `start + len` equals the one of generation `start`.
+    Cycle(Cycle),
+    /// Ran out of steps before reaching a fixpoint or a cycle.
+    StepLimit,
+}
+
+/// `Send + Sync` with the `rayon` feature, where cells and rules are shared between threads.
+#[cfg(feature = "rayon")]
+pub trait MaybeSync: Send + Sync {}
+#[cfg(feature = "rayon")]
+impl<T: Send + Sync> MaybeSync for T {}
+/// `Send + Sync` with the `rayon` feature, where cells and rules are shared between threads.
+#[cfg(not(feature = "rayon"))]
+pub trait MaybeSync {}
+#[cfg(not(feature = "rayon"))]
+impl<T> MaybeSync for T {}
+
+/// Cellular automaton over a dense rectangle of cells.
+///
+/// The next state of a cell depends on its current state and the number of live neighbours,
+/// a cell is live unless it holds the default state (e.g. `false`).
+#[derive(Debug, Clone)]
+pub struct Automaton<C> {
+    size: UVec2,
+    topology: Topology,
+    neighbourhood: Neighbourhood,
+    cells: Vec<C>,
+    /// Back buffer of full steps, swapped with `cells` afterwards.
+    next: Vec<C>,
+    incremental: bool,
+    /// Cells the next incremental step evaluates, `None` for all of them.
+    active: Option<Vec<usize>>,
+    #[cfg(feature = "rayon")]
+    parallel: bool,
+    generation: usize,
+    /// Order independent hash of `cells`, updated with every changed cell.
+    hash: u64,
+}
+impl<C: Copy + Default + Eq + Hash + MaybeSync> Automaton<C> {
+    /// Cells in row order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is not exactly one cell per tile of `size`.
+    pub fn new(size: impl Into<UVec2>, cells: Vec<C>) -> Self {
+        let size = size.into();
+        assert_eq!(
+            cells.len(),
+            size.element_product() as usize,
+            "cell count does not match the size {size}"
+        );
+        let hash = cells.iter().enumerate().fold(0, |hash: u64, (i, cell)| {
+            hash.wrapping_add(cell_hash(i, *cell))
+        });
+        Self {
+            size,
+            topology: Topology::default(),
+            neighbourhood: Neighbourhood::default(),
+            next: cells.clone(),
+            cells,
+            incremental: false,
+            active: None,
+            #[cfg(feature = "rayon")]
+            parallel: false,
+            generation: 0,
+            hash,
+        }
+    }
+
+    /// Maps every tile of the grid to a cell, obstacles get `None`. Keeps the grid [`Topology`].
+    pub fn from_grid<T>(grid: &Grid<T>, mut cell: impl FnMut(UVec2, Option<&T>) -> C) -> Self {
+        let cells = grid_iter(grid.size())
+            .map(|tile| cell(tile, grid.walkable_tiles().get(&tile)))
+            .collect();
+        Self::new(grid.size(), cells).with_topology(grid.topology())
+    }
+
+    #[must_use]
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    #[must_use]
+    pub fn with_neighbourhood(mut self, neighbourhood: Neighbourhood) -> Self {
+        self.neighbourhood = neighbourhood;
+        self
+    }
+
+    /// Only re-evaluates the cells next to the ones the previous step changed.
+    ///
+    /// Pays off when activity dies down over time, e.g. when eroding a shape.
+    #[must_use]
+    pub fn with_incremental(mut self, incremental: bool) -> Self {
+        self.incremental = incremental;
+        self
+    }
+
+    /// Evaluates rows (or the active cells of incremental steps) on the rayon thread pool.
+    #[cfg(feature = "rayon")]
+    #[must_use]
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    #[must_use]
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// Number of steps taken so far.
+    #[must_use]
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Cells in row order.
+    #[must_use]
+    pub fn cells(&self) -> &[C] {
+        &self.cells
+    }
+
+    #[must_use]
+    pub fn get(&self, tile: UVec2) -> Option<C> {
+        tile.cmplt(self.size)
+            .all()
+            .then(|| self.cells[self.index(tile)])
+    }
+
+    #[must_use]
+    pub fn live_count(&self) -> usize {
+        self.cells.iter().filter(|c| **c != C::default()).count()
+    }
+
+    /// Advances one generation, returns the number of changed cells.
+    pub fn step(&mut self, rule: impl Fn(C, u8) -> C + MaybeSync) -> usize {
+        let changed = if self.incremental {
+            self.step_incremental(&rule)
+        } else {
+            self.step_full(&rule)
+        };
+        self.generation += 1;
+        changed
+    }
+
+    /// Steps until nothing changes, a previous state repeats or `max_steps` steps ran.
+    ///
+    /// Repeats are found with Brent's algorithm on a single snapshot, compared by hash first
+    /// and cell by cell after that. The start of a cycle is located by replaying a copy of the
+    /// initial state, which does not count towards `max_steps`. After a cycle the automaton is
+    /// at generation `start + len`.
+    pub fn run(&mut self, rule: impl Fn(C, u8) -> C + MaybeSync, max_steps: usize) -> RunOutcome {
+        let initial = self.clone();
+        let mut checkpoint = Checkpoint::of(self);
+        let mut power = 1;
+        for _ in 0..max_steps {
+            if self.step(&rule) == 0 {
+                return RunOutcome::Fixpoint {
+                    generation: self.generation - 1,
+                };
+            }
+            let len = self.generation - checkpoint.generation;
+            if checkpoint.matches(self) {
+                let cycle = initial.cycle_start(&rule, len);
+                *self = cycle.1;
+                return RunOutcome::Cycle(cycle.0);
+            }
+            if len == power {
+                checkpoint = Checkpoint::of(self);
+                power *= 2;
+            }
+        }
+        RunOutcome::StepLimit
+    }
+
+    /// Cycle of length `len` reached from this state and the automaton at its first repeat.
+    fn cycle_start(self, rule: &(impl Fn(C, u8) -> C + MaybeSync), len: usize) -> (Cycle, Self) {
+        let mut tortoise = self;
+        let mut hare = tortoise.clone();
+        for _ in 0..len {
+            hare.step(rule);
+        }
+        while !Checkpoint::of(&tortoise).matches(&hare) {
+            tortoise.step(rule);
+            hare.step(rule);
+        }
+        (
+            Cycle {
+                start: tortoise.generation,
+                len,
+            },
+            hare,
+        )
+    }
+
+    fn step_full(&mut self, rule: &(impl Fn(C, u8) -> C + MaybeSync)) -> usize {
+        let width = (self.size.x as usize).max(1);
+        let mut next = std::mem::take(&mut self.next);
+        let eval_row = |(y, row): (usize, &mut [C])| {
+            for (x, cell) in row.iter_mut().enumerate() {
+                let i = y * width + x;
+                *cell = rule(self.cells[i], self.live_neighbours(i));
+            }
+        };
+        #[cfg(feature = "rayon")]
+        if self.parallel {
+            next.par_chunks_mut(width).enumerate().for_each(eval_row);
+        } else {
+            next.chunks_mut(width).enumerate().for_each(eval_row);
+        }
+        #[cfg(not(feature = "rayon"))]
+        next.chunks_mut(width).enumerate().for_each(eval_row);
+
+        let mut changed = 0;
+        for (i, (old, new)) in self.cells.iter().zip(&next).enumerate() {
+            if old != new {
+                changed += 1;
+                self.hash = self
+                    .hash
+                    .wrapping_sub(cell_hash(i, *old))
+                    .wrapping_add(cell_hash(i, *new));
+            }
+        }
+        self.next = std::mem::replace(&mut self.cells, next);
+        self.active = None;
+        changed
+    }
+
+    fn step_incremental(&mut self, rule: &(impl Fn(C, u8) -> C + MaybeSync)) -> usize {
+        let active = self
+            .active
+            .take()
+            .unwrap_or_else(|| (0..self.cells.len()).collect());
+        let eval = |i: &usize| {
+            let new = rule(self.cells[*i], self.live_neighbours(*i));
+            (new != self.cells[*i]).then_some((*i, new))
+        };
+        #[cfg(feature = "rayon")]
+        let changes: Vec<_> = if self.parallel {
+            active.par_iter().filter_map(eval).collect()
+        } else {
+            active.iter().filter_map(eval).collect()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let changes: Vec<_> = active.iter().filter_map(eval).collect();
+
+        // Nothing else can change in the next step.
+        let mut next_active = Vec::new();
+        for (i, new) in &changes {
+            self.hash = self
+                .hash
+                .wrapping_sub(cell_hash(*i, self.cells[*i]))
+                .wrapping_add(cell_hash(*i, *new));
+            self.cells[*i] = *new;
+            next_active.extend(once(*i).chain(self.neighbour_indices(*i)));
+        }
+        next_active.sort_unstable();
+        next_active.dedup();
+        self.active = Some(next_active);
+        changes.len()
+    }
+
+    fn live_neighbours(&self, i: usize) -> u8 {
+        self.neighbour_indices(i)
+            .filter(|n| self.cells[*n] != C::default())
+            .count() as u8
+    }
+
+    fn neighbour_indices(&self, i: usize) -> impl Iterator<Item = usize> + '_ {
+        let width = self.size.x as usize;
+        let tile = IVec2::new((i % width) as i32, (i / width) as i32);
+        self.neighbourhood
+            .dirs()
+            .iter()
+            .filter_map(move |dir| self.resolve(tile + *dir))
+            .map(|tile| self.index(tile))
+    }
+
+    fn resolve(&self, tile: IVec2) -> Option<UVec2> {
+        let size = self.size.as_ivec2();
+        match self.topology {
+            Topology::Bounded => {
+                (tile.min_element() >= 0 && tile.cmplt(size).all()).then(|| tile.as_uvec2())
+            }
+            Topology::Toroidal => Some(tile.rem_euclid(size).as_uvec2()),
+        }
+    }
+
+    fn index(&self, tile: UVec2) -> usize {
+        tile.y as usize * self.size.x as usize + tile.x as usize
+    }
+}
+
+fn cell_hash<C: Hash>(i: usize, cell: C) -> u64 {
+    BuildHasherDefault::<DefaultHasher>::default().hash_one((i, cell))
+}
+
+/// Snapshot of the cells of one generation.
+struct Checkpoint<C> {
+    generation: usize,
+    hash: u64,
+    cells: Vec<C>,
+}
+impl<C: Copy + Default + Eq + Hash + MaybeSync> Checkpoint<C> {
+    fn of(automaton: &Automaton<C>) -> Self {
+        Self {
+            generation: automaton.generation,
+            hash: automaton.hash,
+            cells: automaton.cells.clone(),
+        }
+    }
+
+    /// Whether the automaton is in the same state, a hash collision alone does not match.
+    fn matches(&self, automaton: &Automaton<C>) -> bool {
+        self.hash == automaton.hash && self.cells == automaton.cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    fn life(alive: bool, neighbours: u8) -> bool {
+        neighbours == 3 || (alive && neighbours == 2)
+    }
+
+    fn automaton(text: &str) -> Automaton<bool> {
+        let rows: Vec<_> = text.lines().map(str::trim).collect();
+        let size = UVec2::new(rows[0].len() as u32, rows.len() as u32);
+        let cells = rows
+            .iter()
+            .flat_map(|row| row.chars().map(|c| c == '#'))
+            .collect();
+        Automaton::new(size, cells)
+    }
+
+    #[test]
+    #[traced_test]
+    fn blinker() {
+        let mut life_grid = automaton(".....\n..#..\n..#..\n..#..\n.....");
+        assert_eq!(life_grid.step(life), 4);
+        assert_eq!(life_grid.get(UVec2::new(1, 2)), Some(true));
+        assert_eq!(
+            life_grid.run(life, 10),
+            RunOutcome::Cycle(Cycle { start: 1, len: 2 })
+        );
+        assert_eq!(life_grid.generation(), 3);
+        assert_eq!(life_grid.live_count(), 3);
+    }
+
+    #[test_case("....\n.##.\n.##.\n....", 5 => RunOutcome::Fixpoint { generation: 0 }; "block")]
+    #[test_case("....\n.#..\n..#.\n....", 5 => RunOutcome::Fixpoint { generation: 1 }; "dies")]
+    #[test_case(".#.\n.#.\n.#.", 1 => RunOutcome::StepLimit; "limit")]
+    #[traced_test]
+    fn run(text: &str, max_steps: usize) -> RunOutcome {
+        automaton(text).run(life, max_steps)
+    }
+
+    #[test_case(false; "full")]
+    #[test_case(true; "incremental")]
+    #[traced_test]
+    fn glider(incremental: bool) {
+        let mut life_grid = automaton(
+            ".#......\n..#.....\n###.....\n........\n........\n........\n........\n........",
+        )
+        .with_topology(Topology::Toroidal)
+        .with_incremental(incremental);
+        assert_eq!(
+            life_grid.run(life, 100),
+            RunOutcome::Cycle(Cycle { start: 0, len: 32 })
+        );
+    }
+
+    #[test]
+    #[traced_test]
+    fn hash_collision() {
+        let life_grid = automaton("#.\n..");
+        let mut checkpoint = Checkpoint::of(&life_grid);
+        assert!(checkpoint.matches(&life_grid));
+        checkpoint.cells[0] = false;
+        assert!(!checkpoint.matches(&life_grid));
+    }
+
+    #[test_case(false; "full")]
+    #[test_case(true; "incremental")]
+    #[traced_test]
+    fn cycle_after_lead_in(incremental: bool) {
+        // A T-tetromino grows into four blinkers.
+        let mut life_grid = automaton(
+            ".........\n.........\n.........\n...###...\n....#....\n.........\n.........\n.........\n.........",
+        )
+        .with_incremental(incremental);
+        assert_eq!(
+            life_grid.run(life, 100),
+            RunOutcome::Cycle(Cycle { start: 11, len: 2 })
+        );
+        assert_eq!(life_grid.generation(), 13);
+    }
+
+    #[test]
+    #[traced_test]
+    fn incremental_matches_full() {
+        let text = "#..#.##\n.##...#\n#.#.#..\n...##.#\n##.....\n.#.#.##";
+        let mut full = automaton(text).with_neighbourhood(Neighbourhood::VonNeumann);
+        let mut incremental = automaton(text)
+            .with_neighbourhood(Neighbourhood::VonNeumann)
+            .with_incremental(true);
+        let rule = |alive: bool, n: u8| if alive { n % 2 == 1 } else { n >= 2 };
+        for _ in 0..20 {
+            assert_eq!(full.step(rule), incremental.step(rule));
+            assert_eq!(full.cells(), incremental.cells());
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test_case(false)]
+    #[test_case(true)]
+    #[traced_test]
+    fn parallel(incremental: bool) {
+        let text = "#..#.##\n.##...#\n#.#.#..\n...##.#\n##.....\n.#.#.##";
+        let mut serial = automaton(text).with_incremental(incremental);
+        let mut parallel = automaton(text)
+            .with_incremental(incremental)
+            .with_parallel(true);
+        assert_eq!(serial.run(life, 50), parallel.run(life, 50));
+        assert_eq!(serial.cells(), parallel.cells());
+    }
+}
@@ -1,4 +1,4 @@
-use std::{collections::HashSet, marker::PhantomData, str::Lines};
+use std::{collections::HashMap, marker::PhantomData, str::Lines};
 
 use bon::bon;
 use derive_more::derive::{Display, Error};
@@ -10,6 +10,20 @@ pub struct BuiltGrid<T = ()> {
     pub grid: Grid<T>,
     pub start_tile: Option<UVec2>,
     pub end_tile: Option<UVec2>,
+    /// Tiles classified as [`TileKind::Marker`], by marker name in row order.
+    pub markers: HashMap<&'static str, Vec<UVec2>>,
+}
+
+/// How a tile char ends up in the grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TileKind<T> {
+    Wall,
+    Walkable(T),
+    /// Left to the `obstacle`, `start_character` and `end_character` of the builder, any
+    /// other char is walkable with the default value.
+    Skip,
+    /// Walkable with the default value, remembered in [`BuiltGrid::markers`].
+    Marker(&'static str),
 }
 
 #[derive(Debug, Error, Display)]
@@ -21,12 +35,15 @@ pub enum BuildGridError {
     MissingEndTile,
     #[display("Multiple end tiles: {_0} and {_1}")]
     MultipleEndTiles(UVec2, UVec2),
+    #[display("Row {row} has {len} tiles, expected {width}")]
+    RaggedRow {
+        row: usize,
+        len: usize,
+        width: usize,
+    },
 }
 
-pub struct GridBuilder<TValue = (), TProcessCtx = ()>(
-    PhantomData<TValue>,
-    PhantomData<TProcessCtx>,
-);
+pub struct GridBuilder<TValue = ()>(PhantomData<TValue>);
 
 #[bon]
 impl<TValue: Default> GridBuilder<TValue> {
@@ -39,9 +56,8 @@ impl<TValue: Default> GridBuilder<TValue> {
     ) -> Result<BuiltGrid<TValue>, BuildGridError> {
         Self::build_obstacle_grid_from_lines_impl(
             &mut input.lines(),
-            obstacle,
-            &mut (),
-            None,
+            Some(obstacle),
+            |_, _| TileKind::Skip,
             start_character,
             end_character,
         )
@@ -56,45 +72,41 @@ impl<TValue: Default> GridBuilder<TValue> {
     ) -> Result<BuiltGrid<TValue>, BuildGridError> {
         Self::build_obstacle_grid_from_lines_impl(
             lines,
-            obstacle,
-            &mut (),
-            None,
+            Some(obstacle),
+            |_, _| TileKind::Skip,
             start_character,
             end_character,
         )
     }
-}
 
-#[bon]
-impl<TValue: Default, TProcessCtx> GridBuilder<TValue, TProcessCtx> {
+    /// Classifies every tile with `classify_tile` first, e.g. to collect entities while
+    /// building the grid.
     #[builder]
-    pub fn build_obstacle_grid_from_lines_with_processing<'a>(
-        lines: &'_ mut Lines<'a>,
-        obstacle: char,
-        process_ctx: &mut TProcessCtx,
-        process_tile: fn(&mut TProcessCtx, char, UVec2) -> bool,
+    pub fn build_obstacle_grid_from_lines_with_classifier(
+        lines: &mut Lines<'_>,
+        classify_tile: impl FnMut(char, UVec2) -> TileKind<TValue>,
+        obstacle: Option<char>,
         start_character: Option<char>,
         end_character: Option<char>,
     ) -> Result<BuiltGrid<TValue>, BuildGridError> {
         Self::build_obstacle_grid_from_lines_impl(
             lines,
             obstacle,
-            process_ctx,
-            Some(process_tile),
+            classify_tile,
             start_character,
             end_character,
         )
     }
 
-    fn build_obstacle_grid_from_lines_impl<'a>(
-        lines: &'_ mut Lines<'a>,
-        obstacle: char,
-        process_ctx: &mut TProcessCtx,
-        mut process_tile: Option<fn(&mut TProcessCtx, char, UVec2) -> bool>,
+    fn build_obstacle_grid_from_lines_impl(
+        lines: &mut Lines<'_>,
+        obstacle: Option<char>,
+        mut classify_tile: impl FnMut(char, UVec2) -> TileKind<TValue>,
         start_character: Option<char>,
         end_character: Option<char>,
     ) -> Result<BuiltGrid<TValue>, BuildGridError> {
-        let mut obstacles = HashSet::new();
+        let mut walkable_tiles = HashMap::new();
+        let mut markers = HashMap::<_, Vec<_>>::new();
         let mut size = UVec2::ZERO;
         let mut start_tile = None;
         let mut end_tile = None;
@@ -102,25 +114,29 @@ impl<TValue: Default, TProcessCtx> GridBuilder<TValue, TProcessCtx> {
             for (x, c) in line.chars().enumerate() {
                 let tile = UVec2::new(x as _, y as _);
                 size = tile + UVec2::ONE;
-                if process_tile
-                    .as_mut()
-                    .is_some_and(|process_tile| process_tile(process_ctx, c, tile))
-                {
-                    continue;
-                }
-                if c == obstacle {
-                    obstacles.insert(tile);
-                } else if start_character.is_some_and(|s| c == s) {
-                    if let Some(start_tile) = start_tile {
-                        return Err(BuildGridError::MultipleStartTiles(start_tile, tile));
+                match classify_tile(c, tile) {
+                    TileKind::Wall => continue,
+                    TileKind::Walkable(value) => {
+                        walkable_tiles.insert(tile, value);
+                        continue;
                     }
-                    start_tile = Some(tile);
-                } else if end_character.is_some_and(|e| c == e) {
-                    if let Some(end_tile) = end_tile {
-                        return Err(BuildGridError::MultipleEndTiles(end_tile, tile));
+                    TileKind::Marker(name) => markers.entry(name).or_default().push(tile),
+                    TileKind::Skip if obstacle.is_some_and(|o| c == o) => continue,
+                    TileKind::Skip if start_character.is_some_and(|s| c == s) => {
+                        if let Some(start_tile) = start_tile {
+                            return Err(BuildGridError::MultipleStartTiles(start_tile, tile));
+                        }
+                        start_tile = Some(tile);
                     }
-                    end_tile = Some(tile);
+                    TileKind::Skip if end_character.is_some_and(|e| c == e) => {
+                        if let Some(end_tile) = end_tile {
+                            return Err(BuildGridError::MultipleEndTiles(end_tile, tile));
+                        }
+                        end_tile = Some(tile);
+                    }
+                    TileKind::Skip => {}
                 }
+                walkable_tiles.insert(tile, TValue::default());
             }
         }
         if size == UVec2::ZERO {
@@ -133,9 +149,44 @@ impl<TValue: Default, TProcessCtx> GridBuilder<TValue, TProcessCtx> {
             return Err(BuildGridError::MissingEndTile);
         }
         Ok(BuiltGrid {
-            grid: Grid::from_obstacles(obstacles, size),
+            grid: Grid::new(walkable_tiles, size),
             start_tile,
             end_tile,
+            markers,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_test::traced_test;
+
+    #[test]
+    #[traced_test]
+    fn classifier() {
+        let input = "#####\n#.O@#\n#O..#\n#####\n\n<^^>";
+        let mut lines = input.lines();
+        let mut crates = Vec::new();
+        let built = GridBuilder::<u32>::build_obstacle_grid_from_lines_with_classifier()
+            .lines(&mut lines)
+            .classify_tile(|c, tile| match c {
+                'O' => {
+                    crates.push(tile);
+                    TileKind::Walkable(1)
+                }
+                '@' => TileKind::Marker("robot"),
+                '.' => TileKind::Walkable(0),
+                _ => TileKind::Skip,
+            })
+            .obstacle('#')
+            .call()
+            .unwrap();
+        assert_eq!(crates, [UVec2::new(2, 1), UVec2::new(1, 2)]);
+        assert_eq!(built.markers["robot"], [UVec2::new(3, 1)]);
+        assert_eq!(built.grid.walkable_tiles().len(), 6);
+        assert_eq!(built.grid.walkable_tiles()[&UVec2::new(1, 2)], 1);
+        assert!(built.grid.is_obstacle(glam::IVec2::ZERO));
+        assert_eq!(lines.next(), Some("<^^>"));
+    }
+}
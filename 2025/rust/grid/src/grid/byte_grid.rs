@@ -0,0 +1,207 @@
+use glam::{IVec2, UVec2};
+
+use crate::{
+    dir::{DIRS_4, DIRS_8},
+    iter::grid_iter,
+};
+
+use super::{builder::BuildGridError, Grid, Neigbour};
+
+/// Read-only view of a rectangular text grid borrowing the input, tiles are its bytes.
+///
+/// Rows are `stride` bytes apart, the line break included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteGrid<'a> {
+    bytes: &'a [u8],
+    size: UVec2,
+    stride: usize,
+}
+impl<'a> ByteGrid<'a> {
+    /// Takes the lines up to the first empty one (or the end of the input).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for an empty grid or rows of different lengths.
+    pub fn new(input: &'a str) -> Result<Self, BuildGridError> {
+        let width = input.find('\n').unwrap_or(input.len());
+        let line_break = usize::from(input.as_bytes().get(width) == Some(&b'\n'));
+        let (width, line_break) = match input[..width].strip_suffix('\r') {
+            Some(row) => (row.len(), line_break + 1),
+            None => (width, line_break),
+        };
+        if width == 0 {
+            return Err(BuildGridError::EmptyGrid);
+        }
+
+        let mut height = 0;
+        for line in input.lines().take_while(|l| !l.is_empty()) {
+            if line.len() != width {
+                return Err(BuildGridError::RaggedRow {
+                    row: height,
+                    len: line.len(),
+                    width,
+                });
+            }
+            height += 1;
+        }
+        let stride = width + line_break;
+        Ok(Self {
+            bytes: &input.as_bytes()[..(height - 1) * stride + width],
+            size: UVec2::new(width as _, height as _),
+            stride,
+        })
+    }
+
+    #[must_use]
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// The grid part of the input.
+    #[must_use]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    #[must_use]
+    pub fn get(&self, tile: UVec2) -> Option<u8> {
+        tile.cmplt(self.size)
+            .all()
+            .then(|| self.bytes[tile.y as usize * self.stride + tile.x as usize])
+    }
+
+    #[must_use]
+    pub fn row(&self, y: u32) -> Option<&'a [u8]> {
+        (y < self.size.y).then(|| {
+            let start = y as usize * self.stride;
+            &self.bytes[start..start + self.size.x as usize]
+        })
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [u8]> + '_ {
+        (0..self.size.y).filter_map(|y| self.row(y))
+    }
+
+    /// Every tile with its byte in row order.
+    pub fn tiles(&self) -> impl Iterator<Item = (UVec2, u8)> + '_ {
+        grid_iter(self.size).map(|tile| (tile, self.bytes[self.index(tile)]))
+    }
+
+    /// First tile holding `byte` in row order.
+    #[must_use]
+    pub fn find(&self, byte: u8) -> Option<UVec2> {
+        self.find_all(byte).next()
+    }
+
+    /// Tiles holding `byte` in row order, line breaks are no tiles.
+    pub fn find_all(&self, byte: u8) -> impl Iterator<Item = UVec2> + '_ {
+        let width = self.size.x as usize;
+        self.bytes
+            .iter()
+            .enumerate()
+            .filter(move |(i, b)| **b == byte && i % self.stride < width)
+            .map(|(i, _)| self.tile(i))
+    }
+
+    /// Neighbours within the grid with their bytes.
+    pub fn neighbours(&self, tile: UVec2) -> impl Iterator<Item = (Neigbour, u8)> + '_ {
+        self.neighbours_impl(DIRS_4, tile)
+    }
+
+    pub fn neighbours_8(&self, tile: UVec2) -> impl Iterator<Item = (Neigbour, u8)> + '_ {
+        self.neighbours_impl(DIRS_8, tile)
+    }
+
+    fn neighbours_impl<const DIRS_LEN: usize>(
+        &self,
+        dirs: [IVec2; DIRS_LEN],
+        tile: UVec2,
+    ) -> impl Iterator<Item = (Neigbour, u8)> + '_ {
+        dirs.into_iter().filter_map(move |dir| {
+            let target = tile.as_ivec2() + dir;
+            if target.min_element() < 0 {
+                return None;
+            }
+            let target = target.as_uvec2();
+            self.get(target)
+                .map(|byte| (Neigbour::new(target, dir), byte))
+        })
+    }
+
+    /// Owned grid, tiles mapped to `None` become obstacles.
+    pub fn to_grid<T>(&self, mut tile: impl FnMut(UVec2, u8) -> Option<T>) -> Grid<T> {
+        Grid::from_walkable_tiles(
+            self.tiles()
+                .filter_map(|(t, byte)| tile(t, byte).map(|value| (t, value))),
+            self.size,
+        )
+    }
+
+    #[must_use]
+    pub fn to_obstacle_grid<T: Default>(&self, obstacle: u8) -> Grid<T> {
+        self.to_grid(|_, byte| (byte != obstacle).then(T::default))
+    }
+
+    fn index(&self, tile: UVec2) -> usize {
+        tile.y as usize * self.stride + tile.x as usize
+    }
+
+    fn tile(&self, i: usize) -> UVec2 {
+        UVec2::new((i % self.stride) as _, (i / self.stride) as _)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    #[test_case("ab\ncd\n")]
+    #[test_case("ab\r\ncd")]
+    #[test_case("ab\ncd\n\nmoves")]
+    #[traced_test]
+    fn view(input: &str) {
+        let grid = ByteGrid::new(input).unwrap();
+        assert_eq!(grid.size(), UVec2::new(2, 2));
+        assert_eq!(grid.get(UVec2::new(1, 1)), Some(b'd'));
+        assert_eq!(grid.get(UVec2::new(2, 0)), None);
+        assert_eq!(grid.find(b'c'), Some(UVec2::new(0, 1)));
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [b"ab", b"cd"]);
+        assert_eq!(
+            grid.neighbours_8(UVec2::ZERO)
+                .map(|(_, b)| b)
+                .collect::<Vec<_>>(),
+            b"bdc"
+        );
+    }
+
+    #[test_case("ab\ncd\n", b'\n')]
+    #[test_case("ab\r\ncd\r\n", b'\r')]
+    #[test_case("ab\r\ncd\r\n", b'\n')]
+    #[traced_test]
+    fn line_breaks_are_no_tiles(input: &str, byte: u8) {
+        let grid = ByteGrid::new(input).unwrap();
+        assert_eq!(grid.find(byte), None);
+        assert_eq!(grid.find_all(byte).count(), 0);
+    }
+
+    #[test_case("" => matches Err(BuildGridError::EmptyGrid))]
+    #[test_case("ab\nc" => matches Err(BuildGridError::RaggedRow { row: 1, len: 1, width: 2 }))]
+    #[traced_test]
+    fn invalid(input: &str) -> Result<ByteGrid<'_>, BuildGridError> {
+        ByteGrid::new(input)
+    }
+
+    #[test]
+    #[traced_test]
+    fn to_grid() {
+        let bytes = ByteGrid::new("#.#\n.S.\n#.#").unwrap();
+        let grid = bytes.to_obstacle_grid::<()>(b'#');
+        assert_eq!(grid.size(), UVec2::new(3, 3));
+        assert_eq!(grid.walkable_tiles().len(), 5);
+        assert_eq!(bytes.find_all(b'#').count(), 4);
+        let values = bytes.to_grid(|_, b| (b != b'#').then_some(b));
+        assert_eq!(values.walkable_tiles()[&UVec2::ONE], b'S');
+    }
+}
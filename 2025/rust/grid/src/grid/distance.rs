@@ -0,0 +1,182 @@
+use glam::{IVec2, UVec2};
+use std::collections::VecDeque;
+
+use crate::iter::manhattan_iter;
+
+use super::Grid;
+
+/// Dense BFS step counts for every tile of a grid, `None` for unreachable tiles.
+#[derive(Debug, Clone)]
+pub struct DistanceField {
+    size: UVec2,
+    distances: Vec<Option<u32>>,
+}
+impl DistanceField {
+    #[must_use]
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    #[must_use]
+    pub fn get(&self, tile: UVec2) -> Option<u32> {
+        self.index(tile).and_then(|i| self.distances[i])
+    }
+
+    /// Reachable tiles with their distance in row order.
+    pub fn iter(&self) -> impl Iterator<Item = (UVec2, u32)> + '_ {
+        let width = self.size.x as usize;
+        self.distances.iter().enumerate().filter_map(move |(i, d)| {
+            d.map(|d| (UVec2::new((i % width) as _, (i / width) as _), d))
+        })
+    }
+
+    /// Tiles reachable within `steps` steps.
+    pub fn within(&self, steps: u32) -> impl Iterator<Item = UVec2> + '_ {
+        self.iter()
+            .filter_map(move |(tile, d)| (d <= steps).then_some(tile))
+    }
+
+    #[must_use]
+    pub fn max_distance(&self) -> Option<u32> {
+        self.distances.iter().flatten().max().copied()
+    }
+
+    fn index(&self, tile: UVec2) -> Option<usize> {
+        (tile.x < self.size.x && tile.y < self.size.y)
+            .then(|| (tile.y * self.size.x + tile.x) as usize)
+    }
+}
+
+impl<T> Grid<T> {
+    /// 4-way BFS over walkable tiles starting at `from`.
+    #[must_use]
+    pub fn distance_field(&self, from: UVec2) -> DistanceField {
+        self.distance_field_multi([from])
+    }
+
+    /// 4-way BFS over walkable tiles starting at all `sources` at once,
+    /// each tile gets the distance to its nearest source.
+    pub fn distance_field_multi(&self, sources: impl IntoIterator<Item = UVec2>) -> DistanceField {
+        let mut field = DistanceField {
+            size: self.size,
+            distances: vec![None; self.size.element_product() as usize],
+        };
+        let mut q = VecDeque::new();
+        for source in sources {
+            if let Some(i) = field.index(source) {
+                if self.walkable_tiles.contains_key(&source) && field.distances[i].is_none() {
+                    field.distances[i] = Some(0);
+                    q.push_back((source, 0));
+                }
+            }
+        }
+        while let Some((tile, distance)) = q.pop_front() {
+            for n in self.neighbours(tile) {
+                let Some(i) = field.index(n.tile) else {
+                    continue;
+                };
+                if field.distances[i].is_none() {
+                    field.distances[i] = Some(distance + 1);
+                    q.push_back((n.tile, distance + 1));
+                }
+            }
+        }
+        field
+    }
+
+    /// Walkable tiles within the manhattan `radius` of `center`, paired with their distance.
+    ///
+    /// Obstacles are ignored, which makes this usable for "shortcut through walls" puzzles.
+    pub fn tiles_within_manhattan(
+        &self,
+        center: UVec2,
+        radius: u32,
+    ) -> impl Iterator<Item = (UVec2, u32)> + '_ {
+        let center = center.as_ivec2();
+        manhattan_iter(center, radius).filter_map(move |tile: IVec2| {
+            let distance = (tile - center).abs().element_sum() as u32;
+            let tile = self.resolve_tile(tile)?;
+            self.walkable_tiles
+                .contains_key(&tile)
+                .then_some((tile, distance))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::builder::GridBuilder;
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    const RACE_TRACK: &str = "###############
+#...#...#.....#
+#.#.#.#.#.###.#
+#S#...#.#.#...#
+#######.#.#.###
+#######.#.#...#
+#######.#.###.#
+###..E#...#...#
+###.#######.###
+#...###...#...#
+#.#####.#.###.#
+#.#...#.#.#...#
+#.#.#.#.#.#.###
+#...#...#...###
+###############";
+
+    #[test]
+    #[traced_test]
+    fn distance_field() {
+        let built = GridBuilder::<()>::build_obstacle_grid()
+            .input(RACE_TRACK)
+            .obstacle('#')
+            .start_character('S')
+            .end_character('E')
+            .call()
+            .unwrap();
+        let field = built.grid.distance_field(built.start_tile.unwrap());
+        assert_eq!(field.get(built.end_tile.unwrap()), Some(84));
+        assert_eq!(field.get(UVec2::ZERO), None);
+        assert_eq!(field.max_distance(), Some(84));
+        assert_eq!(field.within(2).count(), 3);
+    }
+
+    #[test]
+    #[traced_test]
+    fn distance_field_multi() {
+        let grid = Grid::<()>::from_size((5, 1));
+        let field = grid.distance_field_multi([UVec2::ZERO, UVec2::new(4, 0)]);
+        let distances: Vec<_> = field.iter().map(|(_, d)| d).collect();
+        assert_eq!(distances, [0, 1, 2, 1, 0]);
+    }
+
+    #[test_case(2, 64 => 1)]
+    #[test_case(2, 10 => 10)]
+    #[test_case(20, 76 => 3)]
+    #[test_case(20, 70 => 41)]
+    #[traced_test]
+    fn shortcuts(max_len: u32, treshold: u32) -> usize {
+        let built = GridBuilder::<()>::build_obstacle_grid()
+            .input(RACE_TRACK)
+            .obstacle('#')
+            .start_character('S')
+            .end_character('E')
+            .call()
+            .unwrap();
+        let grid = built.grid;
+        let from_start = grid.distance_field(built.start_tile.unwrap());
+        let from_end = grid.distance_field(built.end_tile.unwrap());
+        let best = from_start.get(built.end_tile.unwrap()).unwrap();
+        from_start
+            .iter()
+            .map(|(tile, start_d)| {
+                grid.tiles_within_manhattan(tile, max_len)
+                    .filter_map(|(end, len)| from_end.get(end).map(|end_d| start_d + len + end_d))
+                    .filter(|cost| *cost + treshold <= best)
+                    .count()
+            })
+            .sum()
+    }
+}
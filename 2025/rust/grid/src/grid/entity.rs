@@ -0,0 +1,319 @@
+use glam::{IVec2, UVec2};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::Grid;
+
+pub type EntityId = usize;
+
+/// Object on top of a [`Grid`] covering one or more tiles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entity<K = ()> {
+    pub kind: K,
+    pub tiles: Vec<UVec2>,
+    /// Immovable entities block pushes like grid obstacles do.
+    pub movable: bool,
+}
+
+/// Entities placed on a grid, moved with Sokoban-style pushes.
+#[derive(Debug, Clone)]
+pub struct EntityLayer<K = ()> {
+    entities: Vec<Entity<K>>,
+    occupied: HashMap<UVec2, EntityId>,
+    history: Vec<(Vec<EntityId>, IVec2)>,
+}
+impl<K> Default for EntityLayer<K> {
+    fn default() -> Self {
+        Self {
+            entities: Vec::new(),
+            occupied: HashMap::new(),
+            history: Vec::new(),
+        }
+    }
+}
+impl<K> EntityLayer<K> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the entity, `None` when one of its tiles is already occupied.
+    pub fn add(
+        &mut self,
+        kind: K,
+        tiles: impl IntoIterator<Item = UVec2>,
+        movable: bool,
+    ) -> Option<EntityId> {
+        let tiles: Vec<_> = tiles.into_iter().collect();
+        if tiles.iter().any(|t| self.occupied.contains_key(t)) {
+            return None;
+        }
+        let id = self.entities.len();
+        self.occupied.extend(tiles.iter().map(|t| (*t, id)));
+        self.entities.push(Entity {
+            kind,
+            tiles,
+            movable,
+        });
+        Some(id)
+    }
+
+    #[must_use]
+    pub fn get(&self, id: EntityId) -> Option<&Entity<K>> {
+        self.entities.get(id)
+    }
+
+    #[must_use]
+    pub fn entity_at(&self, tile: UVec2) -> Option<EntityId> {
+        self.occupied.get(&tile).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, &Entity<K>)> {
+        self.entities.iter().enumerate()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Moves the entity one step in `dir` along with everything it pushes.
+    ///
+    /// Either the whole chain moves or nothing does, returns whether it moved.
+    /// Pushes never wrap around the edges of toroidal grids.
+    pub fn try_push<T>(&mut self, grid: &Grid<T>, id: EntityId, dir: impl Into<IVec2>) -> bool {
+        let dir = dir.into();
+        let Some(pushed) = self.pushed_entities(grid, id, dir) else {
+            return false;
+        };
+        self.shift(&pushed, dir);
+        self.history.push((pushed, dir));
+        true
+    }
+
+    /// Reverts the last successful push, returns whether there was one.
+    pub fn undo(&mut self) -> bool {
+        let Some((pushed, dir)) = self.history.pop() else {
+            return false;
+        };
+        self.shift(&pushed, -dir);
+        true
+    }
+
+    fn pushed_entities<T>(
+        &self,
+        grid: &Grid<T>,
+        id: EntityId,
+        dir: IVec2,
+    ) -> Option<Vec<EntityId>> {
+        let mut pushed = vec![id];
+        let mut seen = HashSet::from([id]);
+        let mut q = VecDeque::from([id]);
+        while let Some(id) = q.pop_front() {
+            let entity = self.entities.get(id)?;
+            if !entity.movable {
+                return None;
+            }
+            for tile in &entity.tiles {
+                if !grid.within_bounds(Grid::<T>::move_tile(*tile, dir)) {
+                    return None;
+                }
+                let (target, _) = grid.move_target(*tile, dir)?;
+                if let Some(other) = self.entity_at(target) {
+                    if seen.insert(other) {
+                        pushed.push(other);
+                        q.push_back(other);
+                    }
+                }
+            }
+        }
+        Some(pushed)
+    }
+
+    fn shift(&mut self, ids: &[EntityId], dir: IVec2) {
+        for id in ids {
+            for tile in &self.entities[*id].tiles {
+                self.occupied.remove(tile);
+            }
+        }
+        for id in ids {
+            for tile in &mut self.entities[*id].tiles {
+                *tile = (tile.as_ivec2() + dir).as_uvec2();
+                self.occupied.insert(*tile, *id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_grid_eq, dir::Dir4, grid::builder::GridBuilder};
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Kind {
+        Robot,
+        Crate,
+    }
+
+    const SMALL: &str = "########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+
+<^^>>>vv<v>>v<<";
+
+    const WIDE: &str = "#######
+#...#.#
+#.....#
+#..OO@#
+#..O..#
+#.....#
+#######
+
+<vv<<^^<<^^";
+
+    fn warehouse(input: &str, wide: bool) -> (Grid, EntityLayer<Kind>, EntityId, Vec<Dir4>) {
+        let (map, moves) = input.split_once("\n\n").unwrap();
+        let map = if wide {
+            map.chars()
+                .map(|c| match c {
+                    '#' => "##",
+                    'O' => "[]",
+                    '@' => "@.",
+                    '\n' => "\n",
+                    _ => "..",
+                })
+                .collect()
+        } else {
+            map.to_string()
+        };
+        let grid = GridBuilder::<()>::build_obstacle_grid()
+            .input(&map)
+            .obstacle('#')
+            .call()
+            .unwrap()
+            .grid;
+        let mut layer = EntityLayer::new();
+        let mut robot = None;
+        for (y, line) in map.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                let tile = UVec2::new(x as _, y as _);
+                match c {
+                    '@' => robot = layer.add(Kind::Robot, [tile], true),
+                    'O' => _ = layer.add(Kind::Crate, [tile], true),
+                    '[' => _ = layer.add(Kind::Crate, [tile, tile + UVec2::X], true),
+                    _ => {}
+                }
+            }
+        }
+        let moves = moves
+            .chars()
+            .filter_map(|c| Dir4::try_from(c).ok())
+            .collect();
+        (grid, layer, robot.unwrap(), moves)
+    }
+
+    fn gps_sum(layer: &EntityLayer<Kind>) -> u32 {
+        layer
+            .iter()
+            .filter(|(_, e)| e.kind == Kind::Crate)
+            .map(|(_, e)| e.tiles[0].y * 100 + e.tiles[0].x)
+            .sum()
+    }
+
+    #[test_case(SMALL, false => 2028)]
+    #[test_case(WIDE, true => 618)]
+    #[traced_test]
+    fn push(input: &str, wide: bool) -> u32 {
+        let (grid, mut layer, robot, moves) = warehouse(input, wide);
+        for dir in moves {
+            layer.try_push(&grid, robot, dir);
+        }
+        gps_sum(&layer)
+    }
+
+    #[test]
+    #[traced_test]
+    fn wide_states() {
+        let (grid, mut layer, robot, moves) = warehouse(WIDE, true);
+        let render = |layer: &EntityLayer<Kind>| {
+            let chars = layer.iter().flat_map(|(_, e)| match e.kind {
+                Kind::Robot => vec![(e.tiles[0], '@')],
+                Kind::Crate => vec![(e.tiles[0], '['), (e.tiles[1], ']')],
+            });
+            grid.renderer().overlay_chars(chars, None).render()
+        };
+        for dir in &moves[..3] {
+            layer.try_push(&grid, robot, *dir);
+        }
+        assert_grid_eq!(
+            render(&layer),
+            "
+            ##############
+            ##......##..##
+            ##..........##
+            ##...[][]...##
+            ##....[]....##
+            ##.......@..##
+            ##############
+            "
+        );
+        for dir in &moves[3..] {
+            layer.try_push(&grid, robot, *dir);
+        }
+        assert_grid_eq!(
+            render(&layer),
+            "
+            ##############
+            ##...[].##..##
+            ##...@.[]...##
+            ##....[]....##
+            ##..........##
+            ##..........##
+            ##############
+            "
+        );
+    }
+
+    #[test]
+    #[traced_test]
+    fn undo() {
+        let (grid, mut layer, robot, moves) = warehouse(WIDE, true);
+        let initial = gps_sum(&layer);
+        let pushes = moves
+            .into_iter()
+            .filter(|dir| layer.try_push(&grid, robot, *dir))
+            .count();
+        assert_ne!(gps_sum(&layer), initial);
+        for _ in 0..pushes {
+            assert!(layer.undo());
+        }
+        assert!(!layer.undo());
+        assert_eq!(gps_sum(&layer), initial);
+        assert_eq!(layer.get(robot).unwrap().tiles, [UVec2::new(10, 3)]);
+    }
+
+    #[test]
+    #[traced_test]
+    fn immovable() {
+        let grid = Grid::<()>::from_size((4, 1));
+        let mut layer = EntityLayer::new();
+        let pusher = layer.add((), [UVec2::ZERO], true).unwrap();
+        layer.add((), [UVec2::X], true).unwrap();
+        layer.add((), [UVec2::new(2, 0)], false).unwrap();
+        assert_eq!(layer.add((), [UVec2::X], true), None);
+        assert!(!layer.try_push(&grid, pusher, Dir4::East));
+        assert_eq!(layer.entity_at(UVec2::ZERO), Some(pusher));
+    }
+}
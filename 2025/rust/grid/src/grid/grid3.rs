@@ -0,0 +1,217 @@
+use glam::{IVec3, UVec3};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{
+    dir::{DIRS_26, DIRS_6},
+    iter::grid_iter_3,
+};
+
+use super::Neigbour;
+
+/// Bounded 3D counterpart of [`Grid`](super::Grid), every tile not stored as walkable is
+/// an obstacle (e.g. a solid voxel).
+#[derive(Debug, Clone)]
+pub struct Grid3<T = ()> {
+    size: UVec3,
+    walkable_tiles: HashMap<UVec3, T>,
+}
+impl<T> Grid3<T> {
+    pub fn new(walkable_tiles: impl Into<HashMap<UVec3, T>>, size: impl Into<UVec3>) -> Self {
+        Self {
+            walkable_tiles: walkable_tiles.into(),
+            size: size.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn size(&self) -> UVec3 {
+        self.size
+    }
+
+    #[must_use]
+    pub fn walkable_tiles(&self) -> &HashMap<UVec3, T> {
+        &self.walkable_tiles
+    }
+
+    #[must_use]
+    pub fn within_bounds(&self, tile: IVec3) -> bool {
+        tile.min_element() >= 0 && tile.as_uvec3().cmplt(self.size).all()
+    }
+
+    #[must_use]
+    pub fn is_walkable(&self, tile: IVec3) -> bool {
+        self.within_bounds(tile) && self.walkable_tiles.contains_key(&tile.as_uvec3())
+    }
+
+    #[must_use]
+    pub fn is_obstacle(&self, tile: IVec3) -> bool {
+        self.within_bounds(tile) && !self.walkable_tiles.contains_key(&tile.as_uvec3())
+    }
+
+    #[must_use]
+    pub fn move_target(&self, pos: UVec3, dir: IVec3) -> Option<(UVec3, &T)> {
+        let target = pos.as_ivec3() + dir;
+        if !self.within_bounds(target) {
+            return None;
+        }
+        let target = target.as_uvec3();
+        self.walkable_tiles.get(&target).map(|c| (target, c))
+    }
+
+    /// Face neighbours.
+    #[must_use]
+    pub fn neighbours(&self, tile: UVec3) -> Vec<Neigbour<UVec3, IVec3>> {
+        self.neighbours_impl(DIRS_6, tile)
+    }
+
+    /// Face, edge and corner neighbours.
+    #[must_use]
+    pub fn neighbours_26(&self, tile: UVec3) -> Vec<Neigbour<UVec3, IVec3>> {
+        self.neighbours_impl(DIRS_26, tile)
+    }
+
+    fn neighbours_impl<const DIRS_LEN: usize>(
+        &self,
+        dirs: [IVec3; DIRS_LEN],
+        tile: UVec3,
+    ) -> Vec<Neigbour<UVec3, IVec3>> {
+        dirs.iter()
+            .filter_map(|d| {
+                self.move_target(tile, *d)
+                    .map(|(c, _)| Neigbour::new(c, *d))
+            })
+            .collect()
+    }
+
+    /// Walkable tiles connected to `start` by face neighbours.
+    #[must_use]
+    pub fn flood_fill(&self, start: UVec3) -> HashSet<UVec3> {
+        self.flood_fill_impl(self.walkable_tiles.contains_key(&start).then_some(start))
+    }
+
+    /// Walkable tiles connected to the space around the grid, i.e. the outside air.
+    #[must_use]
+    pub fn flood_fill_outside(&self) -> HashSet<UVec3> {
+        let last = self.size.saturating_sub(UVec3::ONE);
+        self.flood_fill_impl(
+            self.walkable_tiles
+                .keys()
+                .copied()
+                .filter(|t| t.cmpeq(UVec3::ZERO).any() || t.cmpeq(last).any()),
+        )
+    }
+
+    fn flood_fill_impl(&self, starts: impl IntoIterator<Item = UVec3>) -> HashSet<UVec3> {
+        let mut filled: HashSet<_> = starts.into_iter().collect();
+        let mut q: VecDeque<_> = filled.iter().copied().collect();
+        while let Some(tile) = q.pop_front() {
+            for n in self.neighbours(tile) {
+                if filled.insert(n.tile) {
+                    q.push_back(n.tile);
+                }
+            }
+        }
+        filled
+    }
+
+    /// Obstacle faces not touching another obstacle, including the ones facing enclosed pockets.
+    #[must_use]
+    pub fn surface_area(&self) -> usize {
+        self.count_faces(|tile| !self.is_obstacle(tile))
+    }
+
+    /// Obstacle faces reachable from outside of the grid.
+    #[must_use]
+    pub fn exterior_surface_area(&self) -> usize {
+        let outside = self.flood_fill_outside();
+        self.count_faces(|tile| !self.within_bounds(tile) || outside.contains(&tile.as_uvec3()))
+    }
+
+    fn count_faces(&self, exposed: impl Fn(IVec3) -> bool) -> usize {
+        grid_iter_3(self.size)
+            .filter(|tile| !self.walkable_tiles.contains_key(tile))
+            .map(|tile| {
+                DIRS_6
+                    .iter()
+                    .filter(|d| exposed(tile.as_ivec3() + **d))
+                    .count()
+            })
+            .sum()
+    }
+}
+impl<T: Default> Grid3<T> {
+    pub fn from_size(size: impl Into<UVec3>) -> Self {
+        Self::from_obstacles(HashSet::new(), size)
+    }
+
+    pub fn from_obstacles(obstacles: impl Into<HashSet<UVec3>>, size: impl Into<UVec3>) -> Self {
+        let size = size.into();
+        let obstacles = obstacles.into();
+        let walkable_tiles = grid_iter_3(size)
+            .filter(|c| !obstacles.contains(c))
+            .map(|c| (c, T::default()))
+            .collect();
+        Self {
+            size,
+            walkable_tiles,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    const DROPLET: [(u32, u32, u32); 13] = [
+        (2, 2, 2),
+        (1, 2, 2),
+        (3, 2, 2),
+        (2, 1, 2),
+        (2, 3, 2),
+        (2, 2, 1),
+        (2, 2, 3),
+        (2, 2, 4),
+        (2, 2, 6),
+        (1, 2, 5),
+        (3, 2, 5),
+        (2, 1, 5),
+        (2, 3, 5),
+    ];
+
+    fn droplet() -> Grid3 {
+        let cubes: HashSet<_> = DROPLET.iter().map(|c| UVec3::from(*c)).collect();
+        let size = cubes.iter().fold(UVec3::ZERO, |max, c| max.max(*c)) + UVec3::ONE;
+        Grid3::from_obstacles(cubes, size)
+    }
+
+    #[test]
+    #[traced_test]
+    fn surface() {
+        let grid = droplet();
+        assert_eq!(grid.surface_area(), 64);
+        assert_eq!(grid.exterior_surface_area(), 58);
+
+        let pair = Grid3::<()>::from_obstacles(HashSet::from([UVec3::ZERO, UVec3::X]), (2, 1, 1));
+        assert_eq!(pair.surface_area(), 10);
+    }
+
+    #[test]
+    #[traced_test]
+    fn flood_fill() {
+        let grid = droplet();
+        let pocket = UVec3::new(2, 2, 5);
+        assert!(!grid.flood_fill_outside().contains(&pocket));
+        assert_eq!(grid.flood_fill(pocket), HashSet::from([pocket]));
+        assert!(grid.flood_fill(UVec3::new(2, 2, 2)).is_empty());
+    }
+
+    #[test_case(UVec3::ZERO => (3, 7))]
+    #[test_case(UVec3::ONE => (6, 26))]
+    #[traced_test]
+    fn neighbours(tile: UVec3) -> (usize, usize) {
+        let grid = Grid3::<()>::from_size((3, 3, 3));
+        (grid.neighbours(tile).len(), grid.neighbours_26(tile).len())
+    }
+}
@@ -1,49 +1,105 @@
 use glam::{IVec2, UVec2};
-use pathfinding::directed::astar::astar;
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
 
 use crate::{
-    dir::{DIRS_4, DIRS_8},
+    dir::{Dir4, Dir8, DIRS_4, DIRS_8},
     iter::grid_iter,
     UVec2Ext,
 };
 
+mod automaton;
 pub mod builder;
+mod byte_grid;
+mod distance;
+mod entity;
+mod grid3;
+mod ray;
+mod record;
+mod region;
+mod render;
+mod search;
+mod sparse;
+mod summed_area;
+mod transform;
 
-#[derive(Debug)]
+pub use automaton::*;
+pub use byte_grid::*;
+pub use distance::*;
+pub use entity::*;
+pub use grid3::*;
+pub use ray::*;
+pub use record::*;
+pub use region::*;
+pub use render::*;
+pub use search::*;
+pub use sparse::*;
+pub use summed_area::*;
+pub use transform::*;
+
+#[derive(Debug, Clone)]
 pub struct NodePath<TNode> {
     pub path: Vec<TNode>,
     pub cost: u32,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct NodePaths<TNode> {
     pub paths: Vec<Vec<TNode>>,
     pub cost: u32,
 }
 
 #[derive(PartialEq, Eq, Debug, Hash, Clone)]
-pub struct Neigbour {
-    pub tile: UVec2,
-    pub direction: IVec2,
+pub struct Neigbour<TTile = UVec2, TDir = IVec2> {
+    pub tile: TTile,
+    pub direction: TDir,
 }
-impl Neigbour {
+impl<TTile, TDir> Neigbour<TTile, TDir> {
     #[must_use]
-    pub fn new(tile: UVec2, direction: IVec2) -> Self {
-        Self { tile, direction }
+    pub fn new(tile: TTile, direction: impl Into<TDir>) -> Self {
+        Self {
+            tile,
+            direction: direction.into(),
+        }
     }
 }
+impl<TTile> Neigbour<TTile> {
+    /// Direction as [`Dir4`], `None` for diagonal moves.
+    #[must_use]
+    pub fn dir4(&self) -> Option<Dir4> {
+        Dir4::try_from(self.direction).ok()
+    }
+
+    #[must_use]
+    pub fn dir8(&self) -> Option<Dir8> {
+        Dir8::try_from(self.direction).ok()
+    }
+}
+
+/// How moves past the grid edges are resolved.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    /// Moves outside of the grid are rejected.
+    #[default]
+    Bounded,
+    /// Moves outside of the grid wrap around to the opposite edge.
+    Toroidal,
+}
 
 #[derive(Debug)]
 pub struct Grid<T = ()> {
     size: UVec2,
     walkable_tiles: HashMap<UVec2, T>,
+    topology: Topology,
 }
 impl<T> Grid<T> {
     pub fn new(walkable_tiles: impl Into<HashMap<UVec2, T>>, size: impl Into<UVec2>) -> Self {
         Self {
             walkable_tiles: walkable_tiles.into(),
             size: size.into(),
+            topology: Topology::default(),
         }
     }
 
@@ -60,38 +116,45 @@ impl<T> Grid<T> {
                 .map(|(coords, val)| (coords.into(), val.into()))
                 .collect(),
             size: size.into(),
+            topology: Topology::default(),
         }
     }
 
+    #[must_use]
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
     #[must_use]
     pub fn size(&self) -> UVec2 {
         self.size
     }
 
+    #[must_use]
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
     #[must_use]
     pub fn walkable_tiles(&self) -> &HashMap<UVec2, T> {
         &self.walkable_tiles
     }
 
     #[must_use]
-    pub fn move_target(&self, pos: UVec2, dir: IVec2) -> Option<(UVec2, &T)> {
-        let target = pos.as_ivec2() + dir;
-        if !self.within_bounds(target) {
-            return None;
-        }
-        let target = target.as_uvec2();
+    pub fn move_target(&self, pos: UVec2, dir: impl Into<IVec2>) -> Option<(UVec2, &T)> {
+        let target = self.resolve_tile(Self::move_tile(pos, dir))?;
         self.walkable_tiles.get(&target).map(|c| (target, c))
     }
 
     #[must_use]
-    pub fn move_tile(pos: UVec2, dir: IVec2) -> IVec2 {
-        pos.as_ivec2() + dir
+    pub fn move_tile(pos: UVec2, dir: impl Into<IVec2>) -> IVec2 {
+        pos.as_ivec2() + dir.into()
     }
 
     #[must_use]
-    pub fn move_within_bounds(&self, pos: UVec2, dir: IVec2) -> bool {
-        let target = Self::move_tile(pos, dir);
-        self.within_bounds(target)
+    pub fn move_within_bounds(&self, pos: UVec2, dir: impl Into<IVec2>) -> bool {
+        self.resolve_tile(Self::move_tile(pos, dir)).is_some()
     }
 
     #[must_use]
@@ -99,14 +162,53 @@ impl<T> Grid<T> {
         tile.min_element() >= 0 && tile.x < self.size.x as _ && tile.y < self.size.y as _
     }
 
+    /// Wraps the tile around the grid edges regardless of the grid [`Topology`], `None` when
+    /// the grid has no tiles along an axis.
+    #[must_use]
+    pub fn wrap_tile(&self, tile: IVec2) -> Option<UVec2> {
+        (self.size.min_element() > 0).then(|| tile.rem_euclid(self.size.as_ivec2()).as_uvec2())
+    }
+
+    /// Maps the tile onto the grid according to its [`Topology`].
+    #[must_use]
+    pub fn resolve_tile(&self, tile: IVec2) -> Option<UVec2> {
+        match self.topology {
+            Topology::Bounded => self.within_bounds(tile).then(|| tile.as_uvec2()),
+            Topology::Toroidal => self.wrap_tile(tile),
+        }
+    }
+
     #[must_use]
     pub fn is_walkable(&self, tile: IVec2) -> bool {
-        self.within_bounds(tile) && self.walkable_tiles.contains_key(&tile.as_uvec2())
+        self.resolve_tile(tile)
+            .is_some_and(|tile| self.walkable_tiles.contains_key(&tile))
     }
 
     #[must_use]
     pub fn is_obstacle(&self, tile: IVec2) -> bool {
-        self.within_bounds(tile) && !self.walkable_tiles.contains_key(&tile.as_uvec2())
+        self.resolve_tile(tile)
+            .is_some_and(|tile| !self.walkable_tiles.contains_key(&tile))
+    }
+
+    /// Manhattan distance between tiles, taking the shorter way around for toroidal grids.
+    ///
+    /// Toroidal grids wrap the tiles onto the grid first, an empty grid falls back to the plain
+    /// distance.
+    #[must_use]
+    pub fn distance(&self, a: UVec2, b: UVec2) -> u32 {
+        let wrapped = match self.topology {
+            Topology::Bounded => None,
+            Topology::Toroidal => self
+                .wrap_tile(a.as_ivec2())
+                .zip(self.wrap_tile(b.as_ivec2())),
+        };
+        match wrapped {
+            None => a.manhattan_distance(b),
+            Some((a, b)) => {
+                let delta = a.max(b) - a.min(b);
+                delta.min(self.size - delta).element_sum()
+            }
+        }
     }
 
     #[must_use]
@@ -152,59 +254,24 @@ impl<T> Grid<T> {
     ) -> Vec<Neigbour> {
         dirs.iter()
             .filter_map(|d| {
-                let target = Self::move_tile(tile, *d);
-                if self.move_within_bounds(tile, *d) {
-                    Some(Neigbour::new(target.as_uvec2(), *d))
-                } else {
-                    None
-                }
+                self.resolve_tile(Self::move_tile(tile, *d))
+                    .map(|target| Neigbour::new(target, *d))
             })
             .collect()
     }
 
-    #[must_use]
-    pub fn find_path_astar(
-        &self,
-        start: impl Into<UVec2>,
-        end: impl Into<UVec2>,
-    ) -> Option<Vec<UVec2>> {
-        let start = start.into();
-        let end = end.into();
-        astar(
-            &start,
-            |node| self.neighbours(*node).into_iter().map(|n| (n.tile, 1)),
-            |node| node.manhattan_distance(end),
-            |n| *n == end,
-        )
-        .map(|node_path| node_path.0)
-    }
-
     pub fn print_debug_map<TFnFormatWalkable: FnMut(UVec2) -> Option<char>>(
         &self,
         mut format_walkable: TFnFormatWalkable,
     ) {
-        let size = self.size();
-        let mut dbg_map = String::with_capacity(size.element_product() as _);
-        let x_axis = (0..size.x)
-            .map(|i| (i % 10).to_string())
-            .collect::<String>();
-        dbg_map.push_str(&format!("  {}\n", &x_axis));
-        dbg_map.push_str(" 0");
-        let mut prev_y = 0;
-        for tile in grid_iter(size) {
-            if tile.y != prev_y {
-                prev_y = tile.y;
-                dbg_map.push_str(&format!("{:2}", tile.y - 1));
-                dbg_map.push('\n');
-                dbg_map.push_str(&format!("{:2}", tile.y));
-            }
+        let dbg_map = debug_map(IVec2::ZERO, self.size(), |tile| {
+            let tile = tile.as_uvec2();
             if self.walkable_tiles().contains_key(&tile) {
-                dbg_map.push(format_walkable(tile).unwrap_or('.'));
+                format_walkable(tile).unwrap_or('.')
             } else {
-                dbg_map.push('#');
+                '#'
             }
-        }
-        dbg_map.push_str(&format!("\n  {}", &x_axis));
+        });
         println!("{dbg_map}");
     }
 }
@@ -215,6 +282,7 @@ impl<T: Default> Grid<T> {
         Self {
             size,
             walkable_tiles,
+            topology: Topology::default(),
         }
     }
 
@@ -228,6 +296,103 @@ impl<T: Default> Grid<T> {
         Self {
             size,
             walkable_tiles,
+            topology: Topology::default(),
         }
     }
 }
+
+/// Renders the rectangle starting at `origin` with x/y axis labels (mod 10).
+pub(crate) fn debug_map(
+    origin: IVec2,
+    size: UVec2,
+    mut format_tile: impl FnMut(IVec2) -> char,
+) -> String {
+    let mut dbg_map = String::with_capacity(((size.x + 4) * (size.y + 2)) as _);
+    let x_axis = (0..size.x as i32)
+        .map(|x| char::from(b'0' + (origin.x + x).rem_euclid(10) as u8))
+        .collect::<String>();
+    let _ = writeln!(dbg_map, "  {x_axis}");
+    for y in 0..size.y as i32 {
+        let y = origin.y + y;
+        let _ = write!(dbg_map, "{y:2}");
+        dbg_map.extend((0..size.x as i32).map(|x| format_tile(IVec2::new(origin.x + x, y))));
+        let _ = writeln!(dbg_map, "{y:<2}");
+    }
+    let _ = write!(dbg_map, "  {x_axis}");
+    dbg_map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    #[test_case(Topology::Bounded, UVec2::ZERO, IVec2::NEG_X => None)]
+    #[test_case(Topology::Bounded, UVec2::ZERO, IVec2::X => Some(UVec2::X))]
+    #[test_case(Topology::Toroidal, UVec2::ZERO, IVec2::NEG_X => Some(UVec2::new(2, 0)))]
+    #[test_case(Topology::Toroidal, UVec2::new(2, 1), IVec2::ONE => Some(UVec2::ZERO))]
+    #[traced_test]
+    fn move_target(topology: Topology, pos: UVec2, dir: IVec2) -> Option<UVec2> {
+        let grid = Grid::<()>::from_size((3, 2)).with_topology(topology);
+        grid.move_target(pos, dir).map(|(tile, ())| tile)
+    }
+
+    #[test_case(Topology::Bounded => 4)]
+    #[test_case(Topology::Toroidal => 1)]
+    #[traced_test]
+    fn distance(topology: Topology) -> u32 {
+        let grid = Grid::<()>::from_size((5, 5)).with_topology(topology);
+        grid.distance(UVec2::ZERO, UVec2::new(4, 0))
+    }
+
+    #[test_case((5, 5), (1, 0), (12, 0) => 1)]
+    #[test_case((5, 5), (0, 0), (9, 7) => 3)]
+    #[test_case((0, 3), (1, 0), (4, 0) => 3)]
+    #[traced_test]
+    fn toroidal_distance(size: (u32, u32), a: (u32, u32), b: (u32, u32)) -> u32 {
+        let grid = Grid::<()>::from_size(size).with_topology(Topology::Toroidal);
+        grid.distance(a.into(), b.into())
+    }
+
+    #[test]
+    #[traced_test]
+    fn empty_toroidal_grid() {
+        let grid = Grid::<()>::from_size((0, 3)).with_topology(Topology::Toroidal);
+        assert_eq!(grid.wrap_tile(IVec2::ONE), None);
+        assert_eq!(grid.resolve_tile(IVec2::ONE), None);
+        assert!(grid.neighbours(UVec2::ONE).is_empty());
+        assert!(!grid.is_obstacle(IVec2::ONE));
+    }
+
+    #[test]
+    #[traced_test]
+    fn toroidal_neighbours() {
+        let grid = Grid::<()>::from_size((3, 3)).with_topology(Topology::Toroidal);
+        assert_eq!(grid.neighbours(UVec2::ZERO).len(), 4);
+        assert_eq!(grid.neighbours_8(UVec2::ZERO).len(), 8);
+    }
+
+    #[test]
+    #[traced_test]
+    fn dir_moves() {
+        let grid = Grid::<()>::from_size((3, 3));
+        assert_eq!(
+            grid.move_target(UVec2::ONE, Dir4::North),
+            Some((UVec2::new(1, 0), &()))
+        );
+        assert!(!grid.move_within_bounds(UVec2::ZERO, Dir8::NorthWest));
+        let dirs: Vec<_> = grid
+            .neighbours(UVec2::ONE)
+            .iter()
+            .filter_map(Neigbour::dir4)
+            .collect();
+        assert_eq!(dirs, Dir4::ALL);
+    }
+
+    #[test]
+    fn debug_map_labels() {
+        let map = debug_map(IVec2::new(-1, 9), UVec2::new(3, 2), |_| '.');
+        assert_eq!(map, "  901\n 9...9 \n10...10\n  901");
+    }
+}
@@ -0,0 +1,221 @@
+use glam::{IVec2, UVec2};
+
+use crate::{dir::Dir4, iter::line_iter};
+
+use super::Grid;
+
+/// Outcome of walking straight from a tile until something stops the walk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RayHit {
+    /// Last tile reached before stopping, the start tile when the first step is blocked.
+    pub last: UVec2,
+    /// Obstacle that stopped the walk, `None` when it left the grid.
+    pub obstacle: Option<UVec2>,
+}
+
+/// Precomputed [`RayHit`]s for every tile and [`Dir4`], making each straight walk O(1).
+///
+/// Built for the obstacles at construction time and always treats the grid as bounded.
+#[derive(Debug, Clone)]
+pub struct JumpTable {
+    size: UVec2,
+    hits: Vec<[RayHit; 4]>,
+}
+impl JumpTable {
+    #[must_use]
+    pub fn jump(&self, from: UVec2, dir: Dir4) -> Option<RayHit> {
+        (from.x < self.size.x && from.y < self.size.y)
+            .then(|| self.hits[(from.y * self.size.x + from.x) as usize][dir as usize])
+    }
+}
+
+impl<T> Grid<T> {
+    /// Tiles after `from` stepping by `dir`, stopping before the first obstacle or at the
+    /// grid bounds (or when a toroidal ray gets back to `from` wrapped onto the grid).
+    pub fn ray(&self, from: UVec2, dir: impl Into<IVec2>) -> impl Iterator<Item = UVec2> + '_ {
+        self.ray_through(from, dir)
+            .take_while(|tile| self.walkable_tiles.contains_key(tile))
+    }
+
+    /// Same as [`Grid::ray`] but passing through obstacles.
+    pub fn ray_through(
+        &self,
+        from: UVec2,
+        dir: impl Into<IVec2>,
+    ) -> impl Iterator<Item = UVec2> + '_ {
+        let dir = dir.into();
+        let mut tile = from.as_ivec2();
+        let start = self.wrap_tile(tile);
+        std::iter::from_fn(move || {
+            if dir == IVec2::ZERO {
+                return None;
+            }
+            tile += dir;
+            let resolved = self.resolve_tile(tile)?;
+            tile = resolved.as_ivec2();
+            (Some(resolved) != start).then_some(resolved)
+        })
+    }
+
+    /// Walks from `from` in `dir` until the next step hits an obstacle or leaves the grid.
+    #[must_use]
+    pub fn first_obstacle(&self, from: UVec2, dir: impl Into<IVec2>) -> RayHit {
+        let mut last = from;
+        for tile in self.ray_through(from, dir) {
+            if !self.walkable_tiles.contains_key(&tile) {
+                return RayHit {
+                    last,
+                    obstacle: Some(tile),
+                };
+            }
+            last = tile;
+        }
+        RayHit {
+            last,
+            obstacle: None,
+        }
+    }
+
+    /// Whether the Bresenham line between the tiles crosses walkable tiles only.
+    #[must_use]
+    pub fn line_of_sight(&self, a: UVec2, b: UVec2) -> bool {
+        line_iter(a.as_ivec2(), b.as_ivec2()).all(|tile| self.is_walkable(tile))
+    }
+
+    #[must_use]
+    pub fn jump_table(&self) -> JumpTable {
+        let width = self.size.x as usize;
+        let row_major = || (0..self.size.y).flat_map(|y| (0..self.size.x).map(move |x| (x, y)));
+        let mut hits = vec![
+            [RayHit {
+                last: UVec2::ZERO,
+                obstacle: None,
+            }; 4];
+            self.size.element_product() as usize
+        ];
+        for dir in Dir4::iter() {
+            let step = dir.to_ivec2();
+            // Every tile depends on its neighbour in `dir`, so visit that one first.
+            let tiles: Vec<_> = if step.x > 0 || step.y > 0 {
+                row_major().collect::<Vec<_>>().into_iter().rev().collect()
+            } else {
+                row_major().collect()
+            };
+            for (x, y) in tiles {
+                let tile = UVec2::new(x, y);
+                let next = tile.as_ivec2() + step;
+                let hit = if !self.within_bounds(next) {
+                    RayHit {
+                        last: tile,
+                        obstacle: None,
+                    }
+                } else if !self.walkable_tiles.contains_key(&next.as_uvec2()) {
+                    RayHit {
+                        last: tile,
+                        obstacle: Some(next.as_uvec2()),
+                    }
+                } else {
+                    hits[next.y as usize * width + next.x as usize][dir as usize]
+                };
+                hits[y as usize * width + x as usize][dir as usize] = hit;
+            }
+        }
+        JumpTable {
+            size: self.size,
+            hits,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        grid::{builder::GridBuilder, Topology},
+        iter::grid_iter,
+    };
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    const GUARD: &str = "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+
+    fn guard_grid() -> (Grid, UVec2) {
+        let built = GridBuilder::<()>::build_obstacle_grid()
+            .input(GUARD)
+            .obstacle('#')
+            .start_character('^')
+            .call()
+            .unwrap();
+        (built.grid, built.start_tile.unwrap())
+    }
+
+    #[test_case(IVec2::NEG_Y => 5)]
+    #[test_case(IVec2::X => 5)]
+    #[test_case(IVec2::NEG_X => 2)]
+    #[test_case(IVec2::new(1, -1) => 4)]
+    #[traced_test]
+    fn ray(dir: IVec2) -> usize {
+        let (grid, start) = guard_grid();
+        grid.ray(start, dir).count()
+    }
+
+    #[test]
+    #[traced_test]
+    fn ray_through() {
+        let (grid, start) = guard_grid();
+        let tiles: Vec<_> = grid.ray_through(start, IVec2::new(-2, -1)).collect();
+        assert_eq!(tiles, [UVec2::new(2, 5), UVec2::new(0, 4)]);
+        let grid = grid.with_topology(Topology::Toroidal);
+        assert_eq!(grid.ray_through(start, IVec2::X).count(), 9);
+        let outside = start + UVec2::new(grid.size().x, 0);
+        assert_eq!(grid.ray_through(outside, IVec2::X).count(), 9);
+        assert_eq!(
+            grid.first_obstacle(outside, IVec2::X).obstacle,
+            grid.first_obstacle(start, IVec2::X).obstacle
+        );
+    }
+
+    #[test]
+    #[traced_test]
+    fn first_obstacle() {
+        let (grid, start) = guard_grid();
+        assert_eq!(
+            grid.first_obstacle(start, Dir4::North),
+            RayHit {
+                last: UVec2::new(4, 1),
+                obstacle: Some(UVec2::new(4, 0)),
+            }
+        );
+        assert_eq!(
+            grid.first_obstacle(start, Dir4::East),
+            RayHit {
+                last: UVec2::new(9, 6),
+                obstacle: None,
+            }
+        );
+        assert!(grid.line_of_sight(start, UVec2::new(9, 9)));
+        assert!(!grid.line_of_sight(start, UVec2::new(0, 6)));
+    }
+
+    #[test]
+    #[traced_test]
+    fn jump_table() {
+        let (grid, _) = guard_grid();
+        let table = grid.jump_table();
+        for tile in grid_iter(grid.size()) {
+            for dir in Dir4::iter() {
+                assert_eq!(table.jump(tile, dir), Some(grid.first_obstacle(tile, dir)));
+            }
+        }
+        assert_eq!(table.jump(grid.size(), Dir4::North), None);
+    }
+}
@@ -0,0 +1,405 @@
+use std::io;
+
+use super::GridRenderer;
+
+#[cfg(feature = "record")]
+use glam::UVec2;
+#[cfg(feature = "record")]
+use std::collections::HashMap;
+
+#[cfg(feature = "record")]
+#[derive(Debug, Clone)]
+struct Frame {
+    label: Option<String>,
+    text: String,
+    ansi: String,
+    size: UVec2,
+    pixels: Vec<u8>,
+}
+
+/// Collects rendered grid states of a simulation and exports them as an animation.
+///
+/// Recording only happens with the `record` feature enabled, otherwise the render closures
+/// are never called and all the writers produce no output.
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    frame_delay_ms: u32,
+    #[cfg(feature = "record")]
+    frames: Vec<Frame>,
+}
+impl Default for Recorder {
+    fn default() -> Self {
+        Self {
+            frame_delay_ms: 100,
+            #[cfg(feature = "record")]
+            frames: Vec::new(),
+        }
+    }
+}
+impl Recorder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn frame_delay(mut self, frame_delay_ms: u32) -> Self {
+        self.frame_delay_ms = frame_delay_ms;
+        self
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        #[cfg(feature = "record")]
+        return self.frames.len();
+        #[cfg(not(feature = "record"))]
+        0
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn record<'a, T: 'a>(&mut self, render: impl FnOnce() -> GridRenderer<'a, T>) {
+        self.record_impl(None, render);
+    }
+
+    pub fn record_labelled<'a, T: 'a>(
+        &mut self,
+        label: impl Into<String>,
+        render: impl FnOnce() -> GridRenderer<'a, T>,
+    ) {
+        self.record_impl(Some(label.into()), render);
+    }
+
+    #[cfg(feature = "record")]
+    fn record_impl<'a, T: 'a>(
+        &mut self,
+        label: Option<String>,
+        render: impl FnOnce() -> GridRenderer<'a, T>,
+    ) {
+        let renderer = render().ansi_colors(false);
+        let text = renderer.render();
+        let (size, pixels) = renderer.pixels(1);
+        let ansi = renderer.ansi_colors(true).render();
+        self.frames.push(Frame {
+            label,
+            text,
+            ansi,
+            size,
+            pixels,
+        });
+    }
+
+    #[cfg(not(feature = "record"))]
+    #[allow(clippy::unused_self, clippy::needless_pass_by_value)]
+    fn record_impl<'a, T: 'a>(
+        &mut self,
+        _label: Option<String>,
+        _render: impl FnOnce() -> GridRenderer<'a, T>,
+    ) {
+    }
+
+    /// Looping animated GIF with every tile drawn as a `scale`x`scale` square.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the frames are too large for a GIF or when writing fails.
+    #[cfg_attr(
+        not(feature = "record"),
+        allow(unused_variables, clippy::unused_self, clippy::needless_pass_by_value)
+    )]
+    pub fn write_gif(&self, writer: impl io::Write, scale: u32) -> io::Result<()> {
+        #[cfg(feature = "record")]
+        {
+            let scale = scale.max(1);
+            let size = self
+                .frames
+                .iter()
+                .fold(UVec2::ZERO, |size, f| size.max(f.size))
+                * scale;
+            let (Ok(width), Ok(height)) = (u16::try_from(size.x), u16::try_from(size.y)) else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("frames of size {size} are too large for a GIF"),
+                ));
+            };
+            let mut palette = Palette::default();
+            let indexed: Vec<_> = self
+                .frames
+                .iter()
+                .map(|frame| {
+                    let mut indices = vec![0; size.element_product() as usize];
+                    for (i, rgb) in frame.pixels.chunks_exact(3).enumerate() {
+                        let tile = UVec2::new(i as u32 % frame.size.x, i as u32 / frame.size.x);
+                        let index = palette.index([rgb[0], rgb[1], rgb[2]]);
+                        for y in 0..scale {
+                            let row = (tile.y * scale + y) * size.x;
+                            let from = (row + tile.x * scale) as usize;
+                            indices[from..from + scale as usize].fill(index);
+                        }
+                    }
+                    indices
+                })
+                .collect();
+            let mut encoder =
+                gif::Encoder::new(writer, width, height, &palette.rgb).map_err(io::Error::other)?;
+            encoder
+                .set_repeat(gif::Repeat::Infinite)
+                .map_err(io::Error::other)?;
+            for indices in indexed {
+                let mut frame = gif::Frame::from_indexed_pixels(width, height, indices, None);
+                frame.delay = (self.frame_delay_ms / 10) as u16;
+                encoder.write_frame(&frame).map_err(io::Error::other)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// [asciinema](https://asciinema.org) v2 cast file replaying the (ANSI coloured) frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when writing fails.
+    #[cfg_attr(not(feature = "record"), allow(unused_variables, unused_mut))]
+    pub fn write_cast(&self, mut writer: impl io::Write) -> io::Result<()> {
+        #[cfg(feature = "record")]
+        {
+            let lines = |f: &Frame| f.text.lines().count() + usize::from(f.label.is_some());
+            let width = self
+                .frames
+                .iter()
+                .flat_map(|f| f.text.lines().chain(f.label.as_deref()))
+                .map(|l| l.chars().count())
+                .max()
+                .unwrap_or_default();
+            let height = self.frames.iter().map(lines).max().unwrap_or_default();
+            writeln!(
+                writer,
+                r#"{{"version": 2, "width": {width}, "height": {height}}}"#
+            )?;
+            for (i, frame) in self.frames.iter().enumerate() {
+                let mut screen = String::from("\x1b[2J\x1b[H");
+                if let Some(label) = &frame.label {
+                    screen.push_str(label);
+                    screen.push('\n');
+                }
+                screen.push_str(&frame.ansi);
+                let time_ms = u64::from(self.frame_delay_ms) * i as u64;
+                writeln!(
+                    writer,
+                    r#"[{}.{:03}, "o", {}]"#,
+                    time_ms / 1000,
+                    time_ms % 1000,
+                    json_string(&screen.replace('\n', "\r\n"))
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Standalone HTML page with play/pause and a frame scrubber.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when writing fails.
+    #[cfg_attr(not(feature = "record"), allow(unused_variables, unused_mut))]
+    pub fn write_html(&self, mut writer: impl io::Write) -> io::Result<()> {
+        #[cfg(feature = "record")]
+        {
+            let frames = self
+                .frames
+                .iter()
+                .map(|f| json_string(&f.text))
+                .collect::<Vec<_>>()
+                .join(",\n");
+            let labels = self
+                .frames
+                .iter()
+                .map(|f| json_string(f.label.as_deref().unwrap_or_default()))
+                .collect::<Vec<_>>()
+                .join(",");
+            let last = self.frames.len().saturating_sub(1);
+            let delay = self.frame_delay_ms;
+            write!(
+                writer,
+                r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Grid recording</title>
+<style>
+body {{ background: #1d1f21; color: #e0e0e0; font-family: monospace; }}
+pre {{ line-height: 1; }}
+</style>
+</head>
+<body>
+<div>
+<button id="play">play</button>
+<input id="scrub" type="range" min="0" max="{last}" value="0">
+<span id="index"></span> <span id="label"></span>
+</div>
+<pre id="frame"></pre>
+<script>
+const frames = [
+{frames}
+];
+const labels = [{labels}];
+const scrub = document.getElementById("scrub");
+const play = document.getElementById("play");
+let timer = null;
+function show(i) {{
+  scrub.value = i;
+  document.getElementById("frame").textContent = frames[i] ?? "";
+  document.getElementById("index").textContent = `${{i}}/{last}`;
+  document.getElementById("label").textContent = labels[i] ?? "";
+}}
+scrub.addEventListener("input", () => show(Number(scrub.value)));
+play.addEventListener("click", () => {{
+  if (timer) {{
+    clearInterval(timer);
+    timer = null;
+    play.textContent = "play";
+    return;
+  }}
+  play.textContent = "pause";
+  timer = setInterval(() => show((Number(scrub.value) + 1) % frames.length), {delay});
+}});
+show(0);
+</script>
+</body>
+</html>
+"#
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "record")]
+#[derive(Default)]
+struct Palette {
+    rgb: Vec<u8>,
+    indices: HashMap<[u8; 3], u8>,
+}
+#[cfg(feature = "record")]
+impl Palette {
+    /// Index of the color, the nearest one is used once the 256 color limit is reached.
+    fn index(&mut self, rgb: [u8; 3]) -> u8 {
+        if let Some(index) = self.indices.get(&rgb) {
+            return *index;
+        }
+        let len = self.indices.len();
+        let index = if len < 256 {
+            self.rgb.extend_from_slice(&rgb);
+            len as u8
+        } else {
+            let distance = |c: &[u8]| -> u32 {
+                c.iter()
+                    .zip(rgb)
+                    .map(|(a, b)| u32::from(a.abs_diff(b)).pow(2))
+                    .sum()
+            };
+            self.rgb
+                .chunks_exact(3)
+                .enumerate()
+                .min_by_key(|(_, c)| distance(c))
+                .map_or(0, |(i, _)| i as u8)
+        };
+        self.indices.insert(rgb, index);
+        index
+    }
+}
+
+#[cfg(feature = "record")]
+fn json_string(text: &str) -> String {
+    use std::fmt::Write;
+
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() || c == '<' => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Color, Grid};
+    use glam::UVec2;
+    use tracing_test::traced_test;
+
+    fn record_walk() -> Recorder {
+        let grid = Grid::<()>::from_size((3, 2));
+        let mut recorder = Recorder::new().frame_delay(200);
+        for x in 0..3 {
+            recorder.record_labelled(format!("step {x}"), || {
+                grid.renderer()
+                    .overlay_tiles([UVec2::new(x, 0)], '@', Some(Color::Red))
+            });
+        }
+        recorder
+    }
+
+    #[cfg(not(feature = "record"))]
+    #[test]
+    #[traced_test]
+    fn disabled() {
+        let recorder = record_walk();
+        assert!(recorder.is_empty());
+        let mut out = Vec::new();
+        recorder.write_html(&mut out).unwrap();
+        assert!(out.is_empty());
+    }
+
+    #[cfg(feature = "record")]
+    #[test]
+    #[traced_test]
+    fn gif() {
+        let recorder = record_walk();
+        assert_eq!(recorder.len(), 3);
+        let mut out = Vec::new();
+        recorder.write_gif(&mut out, 4).unwrap();
+        assert_eq!(&out[..6], b"GIF89a");
+        assert_eq!(u16::from_le_bytes([out[6], out[7]]), 12);
+        assert_eq!(u16::from_le_bytes([out[8], out[9]]), 8);
+    }
+
+    #[cfg(feature = "record")]
+    #[test]
+    #[traced_test]
+    fn cast() {
+        let mut out = Vec::new();
+        record_walk().write_cast(&mut out).unwrap();
+        let cast = String::from_utf8(out).unwrap();
+        let lines: Vec<_> = cast.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], r#"{"version": 2, "width": 6, "height": 3}"#);
+        assert!(lines[2].starts_with(r#"[0.200, "o", "\u001b[2J\u001b[Hstep 1\r\n.\u001b[31m@"#));
+    }
+
+    #[cfg(feature = "record")]
+    #[test]
+    #[traced_test]
+    fn html() {
+        let mut out = Vec::new();
+        record_walk().write_html(&mut out).unwrap();
+        let html = String::from_utf8(out).unwrap();
+        assert!(html.contains(r#"max="2""#));
+        assert!(html.contains(r#""..@\n...\n""#));
+        assert!(html.contains(r#"const labels = ["step 0","step 1","step 2"];"#));
+    }
+}
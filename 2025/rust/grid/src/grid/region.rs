@@ -0,0 +1,231 @@
+use glam::{IVec2, UVec2};
+use std::collections::HashSet;
+
+use crate::{
+    dir::{DIRS_4, DIRS_8},
+    iter::grid_iter,
+};
+
+use super::Grid;
+
+/// Connected set of tiles sharing the same key.
+#[derive(Debug, Clone)]
+pub struct Region<K = ()> {
+    pub key: K,
+    pub tiles: HashSet<UVec2>,
+}
+impl<K> Region<K> {
+    #[must_use]
+    pub fn new(key: K, tiles: HashSet<UVec2>) -> Self {
+        Self { key, tiles }
+    }
+
+    #[must_use]
+    pub fn area(&self) -> usize {
+        self.tiles.len()
+    }
+
+    #[must_use]
+    pub fn contains(&self, tile: IVec2) -> bool {
+        tile.min_element() >= 0 && self.tiles.contains(&tile.as_uvec2())
+    }
+
+    /// Count of tile edges not shared with another tile of the region.
+    #[must_use]
+    pub fn perimeter(&self) -> usize {
+        self.tiles
+            .iter()
+            .map(|tile| {
+                DIRS_4
+                    .iter()
+                    .filter(|dir| !self.contains(tile.as_ivec2() + **dir))
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Count of both convex and concave corners (including the ones of holes).
+    #[must_use]
+    pub fn corners(&self) -> usize {
+        self.tiles
+            .iter()
+            .map(|tile| {
+                let tile = tile.as_ivec2();
+                DIRS_4
+                    .iter()
+                    .zip(DIRS_4.iter().cycle().skip(1))
+                    .filter(|(a, b)| {
+                        let a_in = self.contains(tile + **a);
+                        let b_in = self.contains(tile + **b);
+                        let convex = !a_in && !b_in;
+                        let concave = a_in && b_in && !self.contains(tile + **a + **b);
+                        convex || concave
+                    })
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Count of straight sides, which is always equal to the count of corners.
+    #[must_use]
+    pub fn sides(&self) -> usize {
+        self.corners()
+    }
+
+    /// Inclusive `(min, max)` corners of the region.
+    #[must_use]
+    pub fn bounding_box(&self) -> Option<(UVec2, UVec2)> {
+        self.tiles.iter().fold(None, |bounds, tile| {
+            Some(match bounds {
+                Some((min, max)) => (tile.min(min), tile.max(max)),
+                None => (*tile, *tile),
+            })
+        })
+    }
+}
+
+impl<T> Grid<T> {
+    /// Walkable tiles 4-way connected to `start` which satisfy the predicate.
+    pub fn flood_fill(
+        &self,
+        start: UVec2,
+        predicate: impl FnMut(UVec2, &T) -> bool,
+    ) -> HashSet<UVec2> {
+        self.flood_fill_impl(DIRS_4, start, predicate)
+    }
+
+    /// Walkable tiles 8-way connected to `start` which satisfy the predicate.
+    pub fn flood_fill_8(
+        &self,
+        start: UVec2,
+        predicate: impl FnMut(UVec2, &T) -> bool,
+    ) -> HashSet<UVec2> {
+        self.flood_fill_impl(DIRS_8, start, predicate)
+    }
+
+    fn flood_fill_impl<const DIRS_LEN: usize>(
+        &self,
+        dirs: [IVec2; DIRS_LEN],
+        start: UVec2,
+        mut predicate: impl FnMut(UVec2, &T) -> bool,
+    ) -> HashSet<UVec2> {
+        let mut visited = HashSet::new();
+        if !self
+            .walkable_tiles
+            .get(&start)
+            .is_some_and(|val| predicate(start, val))
+        {
+            return visited;
+        }
+        let mut q = vec![start];
+        visited.insert(start);
+        while let Some(tile) = q.pop() {
+            for dir in &dirs {
+                if let Some((target, val)) = self.move_target(tile, *dir) {
+                    if !visited.contains(&target) && predicate(target, val) {
+                        visited.insert(target);
+                        q.push(target);
+                    }
+                }
+            }
+        }
+        visited
+    }
+
+    /// Splits walkable tiles into 4-way connected regions of tiles with equal keys.
+    pub fn connected_components<K: PartialEq>(
+        &self,
+        key_fn: impl FnMut(UVec2, &T) -> K,
+    ) -> Vec<Region<K>> {
+        self.connected_components_impl(DIRS_4, key_fn)
+    }
+
+    /// Splits walkable tiles into 8-way connected regions of tiles with equal keys.
+    pub fn connected_components_8<K: PartialEq>(
+        &self,
+        key_fn: impl FnMut(UVec2, &T) -> K,
+    ) -> Vec<Region<K>> {
+        self.connected_components_impl(DIRS_8, key_fn)
+    }
+
+    fn connected_components_impl<K: PartialEq, const DIRS_LEN: usize>(
+        &self,
+        dirs: [IVec2; DIRS_LEN],
+        mut key_fn: impl FnMut(UVec2, &T) -> K,
+    ) -> Vec<Region<K>> {
+        let mut assigned = HashSet::with_capacity(self.walkable_tiles.len());
+        let mut regions = Vec::new();
+        // iterate in row order to keep the region order stable
+        for start in grid_iter(self.size) {
+            let Some(val) = self.walkable_tiles.get(&start) else {
+                continue;
+            };
+            if assigned.contains(&start) {
+                continue;
+            }
+            let key = key_fn(start, val);
+            let tiles = self.flood_fill_impl(dirs, start, |tile, val| key_fn(tile, val) == key);
+            assigned.extend(tiles.iter().copied());
+            regions.push(Region::new(key, tiles));
+        }
+        regions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::builder::GridBuilder;
+    use crate::testing::parse_grid_with;
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    const GARDEN: &str = "AAAA\nBBCD\nBBCC\nEEEC";
+
+    #[test]
+    #[traced_test]
+    fn flood_fill() {
+        let grid = GridBuilder::<()>::build_obstacle_grid()
+            .input("..#\n.#.\n#..")
+            .obstacle('#')
+            .call()
+            .unwrap()
+            .grid;
+        assert_eq!(grid.flood_fill(UVec2::ZERO, |_, ()| true).len(), 3);
+        assert_eq!(grid.flood_fill_8(UVec2::ZERO, |_, ()| true).len(), 6);
+        assert!(grid.flood_fill(UVec2::new(2, 0), |_, ()| true).is_empty());
+    }
+
+    #[test_case('A' => (4, 10, 4))]
+    #[test_case('B' => (4, 8, 4))]
+    #[test_case('C' => (4, 10, 8))]
+    #[test_case('D' => (1, 4, 4))]
+    #[test_case('E' => (3, 8, 4))]
+    #[traced_test]
+    fn region_metrics(key: char) -> (usize, usize, usize) {
+        let regions = parse_grid_with(GARDEN, Some).connected_components(|_, c| *c);
+        assert_eq!(regions.len(), 5);
+        let region = regions.iter().find(|r| r.key == key).unwrap();
+        (region.area(), region.perimeter(), region.sides())
+    }
+
+    #[test]
+    #[traced_test]
+    fn region_with_holes() {
+        let regions = parse_grid_with("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO", Some)
+            .connected_components(|_, c| *c);
+        let region = regions.iter().find(|r| r.key == 'O').unwrap();
+        assert_eq!(region.area(), 21);
+        assert_eq!(region.perimeter(), 36);
+        assert_eq!(region.sides(), 20);
+        assert_eq!(region.bounding_box(), Some((UVec2::ZERO, UVec2::new(4, 4))));
+    }
+
+    #[test]
+    #[traced_test]
+    fn connected_components_8() {
+        let regions = parse_grid_with("X.X\n.X.\nX..", Some).connected_components_8(|_, c| *c);
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions.iter().filter(|r| r.key == 'X').count(), 1);
+    }
+}
@@ -0,0 +1,380 @@
+use glam::UVec2;
+use std::{
+    collections::HashMap,
+    fmt::{self, Write as _},
+    io,
+};
+
+use crate::iter::grid_iter;
+
+use super::{Grid, GridState, NodePath, NodePaths};
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Rgb(u8, u8, u8),
+}
+impl Color {
+    #[must_use]
+    pub fn rgb(self) -> [u8; 3] {
+        match self {
+            Color::Black => [0, 0, 0],
+            Color::Red => [220, 50, 47],
+            Color::Green => [133, 153, 0],
+            Color::Yellow => [181, 137, 0],
+            Color::Blue => [38, 139, 210],
+            Color::Magenta => [211, 54, 130],
+            Color::Cyan => [42, 161, 152],
+            Color::White => [255, 255, 255],
+            Color::Rgb(r, g, b) => [r, g, b],
+        }
+    }
+
+    fn write_ansi(self, out: &mut String) {
+        let _ = match self {
+            Color::Black => write!(out, "\x1b[30m"),
+            Color::Red => write!(out, "\x1b[31m"),
+            Color::Green => write!(out, "\x1b[32m"),
+            Color::Yellow => write!(out, "\x1b[33m"),
+            Color::Blue => write!(out, "\x1b[34m"),
+            Color::Magenta => write!(out, "\x1b[35m"),
+            Color::Cyan => write!(out, "\x1b[36m"),
+            Color::White => write!(out, "\x1b[37m"),
+            Color::Rgb(r, g, b) => write!(out, "\x1b[38;2;{r};{g};{b}m"),
+        };
+    }
+}
+
+struct Layer {
+    tiles: HashMap<UVec2, char>,
+    color: Option<Color>,
+}
+
+/// Configurable text/image output of a [`Grid`] with overlay layers on top.
+///
+/// Layers added later are drawn over the earlier ones.
+pub struct GridRenderer<'a, T> {
+    grid: &'a Grid<T>,
+    layers: Vec<Layer>,
+    #[allow(clippy::type_complexity)]
+    format_walkable: Option<Box<dyn Fn(UVec2, &T) -> Option<char> + 'a>>,
+    walkable: char,
+    obstacle: char,
+    axis_labels: bool,
+    ansi_colors: bool,
+    walkable_color: Color,
+    obstacle_color: Color,
+}
+impl<'a, T> GridRenderer<'a, T> {
+    #[must_use]
+    pub fn new(grid: &'a Grid<T>) -> Self {
+        Self {
+            grid,
+            layers: Vec::new(),
+            format_walkable: None,
+            walkable: '.',
+            obstacle: '#',
+            axis_labels: false,
+            ansi_colors: false,
+            walkable_color: Color::Rgb(230, 230, 230),
+            obstacle_color: Color::Rgb(40, 40, 40),
+        }
+    }
+
+    #[must_use]
+    pub fn walkable_char(mut self, walkable: char) -> Self {
+        self.walkable = walkable;
+        self
+    }
+
+    #[must_use]
+    pub fn obstacle_char(mut self, obstacle: char) -> Self {
+        self.obstacle = obstacle;
+        self
+    }
+
+    /// Custom walkable tile char, falls back to the walkable char for `None`.
+    #[must_use]
+    pub fn format_walkable(mut self, format: impl Fn(UVec2, &T) -> Option<char> + 'a) -> Self {
+        self.format_walkable = Some(Box::new(format));
+        self
+    }
+
+    /// Row and column labels (mod 10 for columns).
+    #[must_use]
+    pub fn axis_labels(mut self, axis_labels: bool) -> Self {
+        self.axis_labels = axis_labels;
+        self
+    }
+
+    #[must_use]
+    pub fn ansi_colors(mut self, ansi_colors: bool) -> Self {
+        self.ansi_colors = ansi_colors;
+        self
+    }
+
+    /// Base colors used for image output.
+    #[must_use]
+    pub fn tile_colors(mut self, walkable: Color, obstacle: Color) -> Self {
+        self.walkable_color = walkable;
+        self.obstacle_color = obstacle;
+        self
+    }
+
+    #[must_use]
+    pub fn overlay_chars(
+        mut self,
+        tiles: impl IntoIterator<Item = (UVec2, char)>,
+        color: Option<Color>,
+    ) -> Self {
+        self.layers.push(Layer {
+            tiles: tiles.into_iter().collect(),
+            color,
+        });
+        self
+    }
+
+    #[must_use]
+    pub fn overlay_tiles(
+        self,
+        tiles: impl IntoIterator<Item = UVec2>,
+        c: char,
+        color: Option<Color>,
+    ) -> Self {
+        self.overlay_chars(tiles.into_iter().map(|t| (t, c)), color)
+    }
+
+    #[must_use]
+    pub fn overlay_path<S: GridState>(self, path: &NodePath<S>, color: Option<Color>) -> Self {
+        self.overlay_chars(path.tile_chars(), color)
+    }
+
+    #[must_use]
+    pub fn overlay_paths<S: GridState>(self, paths: &NodePaths<S>, color: Option<Color>) -> Self {
+        self.overlay_tiles(paths.unique_tiles(), 'O', color)
+    }
+
+    #[must_use]
+    pub fn render(&self) -> String {
+        let size = self.grid.size();
+        let mut out = String::with_capacity(((size.x + 6) * (size.y + 2)) as _);
+        let x_axis = (0..size.x)
+            .map(|x| char::from(b'0' + (x % 10) as u8))
+            .collect::<String>();
+        if self.axis_labels {
+            let _ = writeln!(out, "   {x_axis}");
+        }
+        for y in 0..size.y {
+            if self.axis_labels {
+                let _ = write!(out, "{y:2} ");
+            }
+            for x in 0..size.x {
+                let (c, color) = self.tile(UVec2::new(x, y));
+                match color {
+                    Some(color) if self.ansi_colors => {
+                        color.write_ansi(&mut out);
+                        out.push(c);
+                        out.push_str(ANSI_RESET);
+                    }
+                    _ => out.push(c),
+                }
+            }
+            if self.axis_labels {
+                let _ = write!(out, " {y}");
+            }
+            out.push('\n');
+        }
+        if self.axis_labels {
+            let _ = writeln!(out, "   {x_axis}");
+        }
+        out
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error when writing fails.
+    pub fn write_to(&self, mut writer: impl io::Write) -> io::Result<()> {
+        writer.write_all(self.render().as_bytes())
+    }
+
+    pub fn print(&self) {
+        print!("{}", self.render());
+    }
+
+    /// RGB pixels with every tile drawn as a `scale`x`scale` square.
+    #[must_use]
+    pub fn pixels(&self, scale: u32) -> (UVec2, Vec<u8>) {
+        let scale = scale.max(1);
+        let size = self.grid.size() * scale;
+        let mut pixels = vec![0; (size.element_product() * 3) as usize];
+        for pixel in grid_iter(size) {
+            let rgb = self.tile_color(pixel / scale).rgb();
+            let i = ((pixel.y * size.x + pixel.x) * 3) as usize;
+            pixels[i..i + 3].copy_from_slice(&rgb);
+        }
+        (size, pixels)
+    }
+
+    /// Binary PPM (P6) image, readable by most image viewers without any extra dependencies.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when writing fails.
+    pub fn write_ppm(&self, mut writer: impl io::Write, scale: u32) -> io::Result<()> {
+        let (size, pixels) = self.pixels(scale);
+        write!(writer, "P6\n{} {}\n255\n", size.x, size.y)?;
+        writer.write_all(&pixels)
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error when encoding or writing fails.
+    #[cfg(feature = "png")]
+    pub fn write_png(&self, writer: impl io::Write, scale: u32) -> Result<(), png::EncodingError> {
+        let (size, pixels) = self.pixels(scale);
+        let mut encoder = png::Encoder::new(writer, size.x, size.y);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&pixels)
+    }
+
+    fn tile(&self, tile: UVec2) -> (char, Option<Color>) {
+        if let Some((c, color)) = self
+            .layers
+            .iter()
+            .rev()
+            .find_map(|l| l.tiles.get(&tile).map(|c| (*c, l.color)))
+        {
+            return (c, color);
+        }
+        match self.grid.walkable_tiles().get(&tile) {
+            Some(val) => (
+                self.format_walkable
+                    .as_ref()
+                    .and_then(|format| format(tile, val))
+                    .unwrap_or(self.walkable),
+                None,
+            ),
+            None => (self.obstacle, None),
+        }
+    }
+
+    fn tile_color(&self, tile: UVec2) -> Color {
+        if let Some(layer) = self
+            .layers
+            .iter()
+            .rev()
+            .find(|l| l.tiles.contains_key(&tile))
+        {
+            return layer.color.unwrap_or(Color::Red);
+        }
+        if self.grid.walkable_tiles().contains_key(&tile) {
+            self.walkable_color
+        } else {
+            self.obstacle_color
+        }
+    }
+}
+impl<T> fmt::Display for GridRenderer<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render())
+    }
+}
+
+impl<T> Grid<T> {
+    #[must_use]
+    pub fn renderer(&self) -> GridRenderer<'_, T> {
+        GridRenderer::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::builder::GridBuilder;
+    use tracing_test::traced_test;
+
+    fn grid() -> Grid {
+        GridBuilder::<()>::build_obstacle_grid()
+            .input("..#\n.#.\n...")
+            .obstacle('#')
+            .call()
+            .unwrap()
+            .grid
+    }
+
+    #[test]
+    #[traced_test]
+    fn render() {
+        let grid = grid();
+        assert_eq!(grid.renderer().render(), "..#\n.#.\n...\n");
+        assert_eq!(
+            grid.renderer().axis_labels(true).render(),
+            "   012\n 0 ..# 0\n 1 .#. 1\n 2 ... 2\n   012\n"
+        );
+    }
+
+    #[test]
+    #[traced_test]
+    fn overlays() {
+        let grid = grid();
+        let path = grid.find_path_bfs((0, 0), (2, 2)).unwrap();
+        let rendered = grid
+            .renderer()
+            .walkable_char(' ')
+            .overlay_tiles([UVec2::new(0, 2), UVec2::new(2, 1)], 'x', None)
+            .overlay_path(&path, None)
+            .to_string();
+        assert_eq!(rendered.lines().count(), 3);
+        assert!(rendered.starts_with('S'));
+        assert!(rendered.ends_with("E\n"));
+        assert_eq!(rendered.matches('x').count(), 1);
+    }
+
+    #[test]
+    #[traced_test]
+    fn ansi_colors() {
+        let grid = grid();
+        let rendered = grid
+            .renderer()
+            .ansi_colors(true)
+            .overlay_tiles([UVec2::ZERO], 'o', Some(Color::Red))
+            .overlay_tiles([UVec2::X], 'o', None)
+            .render();
+        assert!(rendered.starts_with("\x1b[31mo\x1b[0mo#\n"));
+    }
+
+    #[test]
+    #[traced_test]
+    fn ppm() {
+        let grid = grid();
+        let mut out = Vec::new();
+        grid.renderer()
+            .overlay_tiles([UVec2::ZERO], 'o', Some(Color::Blue))
+            .write_ppm(&mut out, 2)
+            .unwrap();
+        let header = b"P6\n6 6\n255\n";
+        assert_eq!(&out[..header.len()], header);
+        assert_eq!(out.len(), header.len() + 6 * 6 * 3);
+        assert_eq!(out[header.len()..header.len() + 3], Color::Blue.rgb());
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    #[traced_test]
+    fn png() {
+        let grid = grid();
+        let mut out = Vec::new();
+        grid.renderer().write_png(&mut out, 4).unwrap();
+        assert_eq!(&out[1..4], b"PNG");
+    }
+}
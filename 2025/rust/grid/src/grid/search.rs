@@ -0,0 +1,532 @@
+use glam::{IVec2, UVec2};
+use pathfinding::directed::{astar::astar, astar::astar_bag, bfs::bfs, dijkstra::dijkstra};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    hash::Hash,
+};
+
+use super::{Grid, Neigbour, NodePath, NodePaths};
+
+/// Search node which stands on a single grid tile, e.g. a tile, tile + facing
+/// or tile + any extra counters.
+pub trait GridState: Clone + Eq + Hash {
+    fn tile(&self) -> UVec2;
+
+    /// Direction of the move which reached the state, `None` when the state does not track it.
+    fn direction(&self) -> Option<IVec2> {
+        None
+    }
+}
+impl GridState for UVec2 {
+    fn tile(&self) -> UVec2 {
+        *self
+    }
+}
+impl GridState for Neigbour {
+    fn tile(&self) -> UVec2 {
+        self.tile
+    }
+
+    fn direction(&self) -> Option<IVec2> {
+        Some(self.direction)
+    }
+}
+
+impl<TNode: GridState> NodePath<TNode> {
+    /// Count of nodes in the path including the start.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.path.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.path.is_empty()
+    }
+
+    /// Count of moves to another tile, in-place state changes (e.g. turns) are not counted.
+    #[must_use]
+    pub fn steps(&self) -> usize {
+        self.moves().count()
+    }
+
+    #[must_use]
+    pub fn unique_tiles(&self) -> HashSet<UVec2> {
+        self.path.iter().map(GridState::tile).collect()
+    }
+
+    /// Count of changes of the movement direction between consecutive moves.
+    #[must_use]
+    pub fn turns(&self) -> usize {
+        self.moves()
+            .zip(self.moves().skip(1))
+            .filter(|((_, a), (_, b))| a != b)
+            .count()
+    }
+
+    /// Path tiles marked by the direction of the move leaving them, `S` and `E` for the ends.
+    #[must_use]
+    pub fn tile_chars(&self) -> HashMap<UVec2, char> {
+        let mut chars: HashMap<_, _> = self
+            .moves()
+            .map(|(tile, dir)| {
+                let c = match dir {
+                    IVec2::NEG_Y => '^',
+                    IVec2::X => '>',
+                    IVec2::Y => 'v',
+                    IVec2::NEG_X => '<',
+                    _ => 'O',
+                };
+                (tile, c)
+            })
+            .collect();
+        if let (Some(start), Some(end)) = (self.path.first(), self.path.last()) {
+            chars.insert(start.tile(), 'S');
+            chars.insert(end.tile(), 'E');
+        }
+        chars
+    }
+
+    pub fn print_debug_map<T>(&self, grid: &Grid<T>) {
+        let chars = self.tile_chars();
+        grid.print_debug_map(|tile| chars.get(&tile).copied());
+    }
+
+    /// Moves between different tiles as `(from, direction)` pairs.
+    ///
+    /// The direction comes from the state when it tracks one, otherwise from the tiles where
+    /// a jump of more than one tile along an axis is a step wrapping around a toroidal grid.
+    fn moves(&self) -> impl Iterator<Item = (UVec2, IVec2)> + '_ {
+        self.path
+            .iter()
+            .zip(self.path.iter().skip(1))
+            .filter(|(a, b)| a.tile() != b.tile())
+            .map(|(a, b)| {
+                let dir = b.direction().unwrap_or_else(|| {
+                    let delta = b.tile().as_ivec2() - a.tile().as_ivec2();
+                    let wrapped = delta.abs().cmpgt(IVec2::ONE);
+                    IVec2::select(wrapped, -delta.signum(), delta.signum())
+                });
+                (a.tile(), dir)
+            })
+    }
+}
+
+impl<TNode: GridState> NodePaths<TNode> {
+    /// Count of the found paths.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Tiles visited by any of the paths.
+    #[must_use]
+    pub fn unique_tiles(&self) -> HashSet<UVec2> {
+        self.paths
+            .iter()
+            .flat_map(|p| p.iter().map(GridState::tile))
+            .collect()
+    }
+
+    /// Each path as a [`NodePath`] sharing the total cost.
+    pub fn iter(&self) -> impl Iterator<Item = NodePath<TNode>> + '_ {
+        self.paths.iter().map(|path| NodePath {
+            path: path.clone(),
+            cost: self.cost,
+        })
+    }
+
+    /// Marks the tiles visited by any of the paths with `O`.
+    pub fn print_debug_map<T>(&self, grid: &Grid<T>) {
+        let tiles = self.unique_tiles();
+        grid.print_debug_map(|tile| tiles.contains(&tile).then_some('O'));
+    }
+}
+
+impl<T> Grid<T> {
+    /// Walkable 4-way neighbours with unit cost.
+    #[must_use]
+    pub fn tile_successors(&self, tile: &UVec2) -> Vec<(UVec2, u32)> {
+        self.neighbours(*tile)
+            .into_iter()
+            .map(|n| (n.tile, 1))
+            .collect()
+    }
+
+    /// Step forward in the facing direction or turn 90° in place.
+    #[must_use]
+    pub fn turning_successors(
+        &self,
+        state: &Neigbour,
+        move_cost: u32,
+        turn_cost: u32,
+    ) -> Vec<(Neigbour, u32)> {
+        let mut successors = Vec::with_capacity(3);
+        if let Some((target, _)) = self.move_target(state.tile, state.direction) {
+            successors.push((Neigbour::new(target, state.direction), move_cost));
+        }
+        successors.push((
+            Neigbour::new(state.tile, -state.direction.perp()),
+            turn_cost,
+        ));
+        successors.push((Neigbour::new(state.tile, state.direction.perp()), turn_cost));
+        successors
+    }
+
+    #[must_use]
+    pub fn find_path_astar(
+        &self,
+        start: impl Into<UVec2>,
+        end: impl Into<UVec2>,
+    ) -> Option<NodePath<UVec2>> {
+        let end = end.into();
+        find_state_path_astar(
+            &start.into(),
+            |tile| self.tile_successors(tile),
+            |tile| self.distance(*tile, end),
+            |tile| *tile == end,
+        )
+    }
+
+    #[must_use]
+    pub fn find_path_dijkstra(
+        &self,
+        start: impl Into<UVec2>,
+        end: impl Into<UVec2>,
+    ) -> Option<NodePath<UVec2>> {
+        let end = end.into();
+        find_state_path(
+            &start.into(),
+            |tile| self.tile_successors(tile),
+            |tile| *tile == end,
+        )
+    }
+
+    #[must_use]
+    pub fn find_path_bfs(
+        &self,
+        start: impl Into<UVec2>,
+        end: impl Into<UVec2>,
+    ) -> Option<NodePath<UVec2>> {
+        let end = end.into();
+        find_state_path_bfs(
+            &start.into(),
+            |tile| self.neighbours(*tile).into_iter().map(|n| n.tile),
+            |tile| *tile == end,
+        )
+    }
+
+    #[must_use]
+    pub fn find_all_paths_astar(
+        &self,
+        start: impl Into<UVec2>,
+        end: impl Into<UVec2>,
+    ) -> Option<NodePaths<UVec2>> {
+        let end = end.into();
+        find_all_state_paths_astar(
+            &start.into(),
+            |tile| self.tile_successors(tile),
+            |tile| self.distance(*tile, end),
+            |tile| *tile == end,
+        )
+    }
+}
+
+/// Breadth-first search over custom search states where every move costs 1.
+pub fn find_state_path_bfs<S, IN>(
+    start: &S,
+    successors: impl FnMut(&S) -> IN,
+    is_end: impl FnMut(&S) -> bool,
+) -> Option<NodePath<S>>
+where
+    S: Clone + Eq + Hash,
+    IN: IntoIterator<Item = S>,
+{
+    bfs(start, successors, is_end).map(|path| NodePath {
+        cost: path.len() as u32 - 1,
+        path,
+    })
+}
+
+/// Dijkstra over custom search states.
+pub fn find_state_path<S, IN>(
+    start: &S,
+    successors: impl FnMut(&S) -> IN,
+    is_end: impl FnMut(&S) -> bool,
+) -> Option<NodePath<S>>
+where
+    S: Clone + Eq + Hash,
+    IN: IntoIterator<Item = (S, u32)>,
+{
+    dijkstra(start, successors, is_end).map(|(path, cost)| NodePath { path, cost })
+}
+
+/// A* over custom search states, the heuristic must not overestimate the remaining cost.
+pub fn find_state_path_astar<S, IN>(
+    start: &S,
+    successors: impl FnMut(&S) -> IN,
+    heuristic: impl FnMut(&S) -> u32,
+    is_end: impl FnMut(&S) -> bool,
+) -> Option<NodePath<S>>
+where
+    S: Clone + Eq + Hash,
+    IN: IntoIterator<Item = (S, u32)>,
+{
+    astar(start, successors, heuristic, is_end).map(|(path, cost)| NodePath { path, cost })
+}
+
+/// All the shortest paths over custom search states.
+///
+/// The count of paths can grow exponentially, prefer [`find_shortest_path_tiles`]
+/// when only the visited tiles matter.
+pub fn find_all_state_paths_astar<S, IN>(
+    start: &S,
+    successors: impl FnMut(&S) -> IN,
+    heuristic: impl FnMut(&S) -> u32,
+    is_end: impl FnMut(&S) -> bool,
+) -> Option<NodePaths<S>>
+where
+    S: Clone + Eq + Hash,
+    IN: IntoIterator<Item = (S, u32)>,
+{
+    astar_bag(start, successors, heuristic, is_end).map(|(paths, cost)| NodePaths {
+        paths: paths.collect(),
+        cost,
+    })
+}
+
+/// Tiles on any of the shortest paths over custom search states along with the path cost.
+pub fn find_shortest_path_tiles<S, IN>(
+    start: &S,
+    mut successors: impl FnMut(&S) -> IN,
+    mut is_end: impl FnMut(&S) -> bool,
+) -> Option<(HashSet<UVec2>, u32)>
+where
+    S: GridState,
+    IN: IntoIterator<Item = (S, u32)>,
+{
+    let mut states = vec![start.clone()];
+    let mut indices = HashMap::from([(start.clone(), 0)]);
+    let mut costs = vec![0];
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new()];
+    let mut q = BinaryHeap::from([Reverse((0, 0))]);
+    let mut end_cost = None;
+    let mut ends = Vec::new();
+    while let Some(Reverse((cost, i))) = q.pop() {
+        if cost > costs[i] {
+            continue;
+        }
+        if end_cost.is_some_and(|end_cost| cost > end_cost) {
+            break;
+        }
+        if is_end(&states[i]) {
+            end_cost = Some(cost);
+            ends.push(i);
+            continue;
+        }
+        for (next, step_cost) in successors(&states[i]) {
+            let next_cost = cost + step_cost;
+            match indices.get(&next) {
+                Some(&n) if next_cost > costs[n] => {}
+                Some(&n) if next_cost == costs[n] => predecessors[n].push(i),
+                Some(&n) => {
+                    costs[n] = next_cost;
+                    predecessors[n] = vec![i];
+                    q.push(Reverse((next_cost, n)));
+                }
+                None => {
+                    let n = states.len();
+                    indices.insert(next.clone(), n);
+                    states.push(next);
+                    costs.push(next_cost);
+                    predecessors.push(vec![i]);
+                    q.push(Reverse((next_cost, n)));
+                }
+            }
+        }
+    }
+
+    let end_cost = end_cost?;
+    let mut visited = HashSet::new();
+    let mut tiles = HashSet::new();
+    while let Some(i) = ends.pop() {
+        if visited.insert(i) {
+            tiles.insert(states[i].tile());
+            ends.extend(predecessors[i].iter().copied());
+        }
+    }
+    Some((tiles, end_cost))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{builder::GridBuilder, Topology};
+    use glam::IVec2;
+    use tracing_test::traced_test;
+
+    const MAZE: &str = "###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############";
+
+    fn maze() -> (Grid, UVec2, UVec2) {
+        let built = GridBuilder::<()>::build_obstacle_grid()
+            .input(MAZE)
+            .obstacle('#')
+            .start_character('S')
+            .end_character('E')
+            .call()
+            .unwrap();
+        (
+            built.grid,
+            built.start_tile.unwrap(),
+            built.end_tile.unwrap(),
+        )
+    }
+
+    #[test]
+    #[traced_test]
+    fn state_path() {
+        let (grid, start, end) = maze();
+        let path = find_state_path(
+            &Neigbour::new(start, IVec2::X),
+            |n| grid.turning_successors(n, 1, 1000),
+            |n| n.tile == end,
+        )
+        .unwrap();
+        assert_eq!(path.cost, 7036);
+        let path_astar = find_state_path_astar(
+            &Neigbour::new(start, IVec2::X),
+            |n| grid.turning_successors(n, 1, 1000),
+            |n| grid.distance(n.tile, end),
+            |n| n.tile == end,
+        )
+        .unwrap();
+        assert_eq!(path_astar.cost, 7036);
+    }
+
+    #[test]
+    #[traced_test]
+    fn all_state_paths() {
+        let (grid, start, end) = maze();
+        let paths = find_all_state_paths_astar(
+            &Neigbour::new(start, IVec2::X),
+            |n| grid.turning_successors(n, 1, 1000),
+            |n| grid.distance(n.tile, end),
+            |n| n.tile == end,
+        )
+        .unwrap();
+        assert_eq!(paths.cost, 7036);
+        assert_eq!(paths.unique_tiles().len(), 45);
+    }
+
+    #[test]
+    #[traced_test]
+    fn shortest_path_tiles() {
+        let (grid, start, end) = maze();
+        let (tiles, cost) = find_shortest_path_tiles(
+            &Neigbour::new(start, IVec2::X),
+            |n| grid.turning_successors(n, 1, 1000),
+            |n| n.tile == end,
+        )
+        .unwrap();
+        assert_eq!(cost, 7036);
+        assert_eq!(tiles.len(), 45);
+    }
+
+    #[test]
+    #[traced_test]
+    fn tile_path() {
+        let (grid, start, end) = maze();
+        let path = find_state_path(&start, |t| grid.tile_successors(t), |t| *t == end).unwrap();
+        assert_eq!(Some(path.cost), grid.distance_field(start).get(end));
+        assert_eq!(path.path.len(), path.cost as usize + 1);
+    }
+
+    #[test]
+    #[traced_test]
+    fn tile_searches_agree() {
+        let (grid, start, end) = maze();
+        let astar = grid.find_path_astar(start, end).unwrap();
+        let dijkstra = grid.find_path_dijkstra(start, end).unwrap();
+        let bfs = grid.find_path_bfs(start, end).unwrap();
+        let all = grid.find_all_paths_astar(start, end).unwrap();
+        assert_eq!(astar.cost, dijkstra.cost);
+        assert_eq!(astar.cost, bfs.cost);
+        assert_eq!(astar.cost, all.cost);
+        assert!(all.iter().all(|p| p.steps() == astar.steps()));
+    }
+
+    #[test]
+    #[traced_test]
+    fn path_helpers() {
+        let path = NodePath {
+            path: vec![
+                Neigbour::new(UVec2::ZERO, IVec2::X),
+                Neigbour::new(UVec2::X, IVec2::X),
+                Neigbour::new(UVec2::X, IVec2::Y),
+                Neigbour::new(UVec2::ONE, IVec2::Y),
+                Neigbour::new(UVec2::new(1, 2), IVec2::Y),
+            ],
+            cost: 1003,
+        };
+        assert_eq!(path.len(), 5);
+        assert_eq!(path.steps(), 3);
+        assert_eq!(path.turns(), 1);
+        assert_eq!(path.unique_tiles().len(), 4);
+        let chars = path.tile_chars();
+        assert_eq!(chars[&UVec2::ZERO], 'S');
+        assert_eq!(chars[&UVec2::X], 'v');
+        assert_eq!(chars[&UVec2::ONE], 'v');
+        assert_eq!(chars[&UVec2::new(1, 2)], 'E');
+    }
+
+    #[test]
+    #[traced_test]
+    fn toroidal_path_helpers() {
+        let grid = Grid::<()>::from_walkable_tiles(
+            [(0, 0), (1, 0), (2, 0), (4, 0)]
+                .map(|t| (UVec2::from(t), ()))
+                .into_iter(),
+            (5, 1),
+        )
+        .with_topology(Topology::Toroidal);
+        let path = grid.find_path_bfs((2, 0), (4, 0)).unwrap();
+        assert_eq!(
+            path.path,
+            [UVec2::new(2, 0), UVec2::X, UVec2::ZERO, UVec2::new(4, 0)]
+        );
+        assert_eq!(path.turns(), 0);
+        let chars = path.tile_chars();
+        assert_eq!(chars[&UVec2::X], '<');
+        assert_eq!(chars[&UVec2::ZERO], '<');
+
+        let path = NodePath {
+            path: vec![
+                Neigbour::new(UVec2::ZERO, IVec2::NEG_X),
+                Neigbour::new(UVec2::new(4, 0), IVec2::NEG_X),
+            ],
+            cost: 1,
+        };
+        assert_eq!(path.tile_chars()[&UVec2::ZERO], 'S');
+        assert_eq!(path.moves().next(), Some((UVec2::ZERO, IVec2::NEG_X)));
+    }
+}
@@ -0,0 +1,207 @@
+use glam::{IVec2, UVec2};
+use std::collections::HashMap;
+
+use crate::dir::{DIRS_4, DIRS_8};
+
+use super::{debug_map, Neigbour};
+
+/// Unbounded grid keyed by signed coordinates.
+///
+/// Only the stored tiles are kept, the bounding box grows and shrinks with them.
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T = ()> {
+    tiles: HashMap<IVec2, T>,
+    bounds: Option<(IVec2, IVec2)>,
+}
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self {
+            tiles: HashMap::new(),
+            bounds: None,
+        }
+    }
+}
+impl<T> SparseGrid<T> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_tiles<IntoV, IntoT>(tiles: impl IntoIterator<Item = (IntoV, IntoT)>) -> Self
+    where
+        IntoV: Into<IVec2>,
+        IntoT: Into<T>,
+    {
+        let mut grid = Self::new();
+        for (tile, val) in tiles {
+            grid.insert(tile.into(), val.into());
+        }
+        grid
+    }
+
+    #[must_use]
+    pub fn tiles(&self) -> &HashMap<IVec2, T> {
+        &self.tiles
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    #[must_use]
+    pub fn get(&self, tile: IVec2) -> Option<&T> {
+        self.tiles.get(&tile)
+    }
+
+    #[must_use]
+    pub fn get_mut(&mut self, tile: IVec2) -> Option<&mut T> {
+        self.tiles.get_mut(&tile)
+    }
+
+    #[must_use]
+    pub fn contains(&self, tile: IVec2) -> bool {
+        self.tiles.contains_key(&tile)
+    }
+
+    pub fn insert(&mut self, tile: IVec2, val: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            Some((min, max)) => (min.min(tile), max.max(tile)),
+            None => (tile, tile),
+        });
+        self.tiles.insert(tile, val)
+    }
+
+    pub fn remove(&mut self, tile: IVec2) -> Option<T> {
+        let removed = self.tiles.remove(&tile)?;
+        if self
+            .bounds
+            .is_some_and(|(min, max)| tile.cmpeq(min).any() || tile.cmpeq(max).any())
+        {
+            self.bounds = self.tiles.keys().fold(None, |bounds, tile| {
+                Some(match bounds {
+                    Some((min, max)) => (tile.min(min), tile.max(max)),
+                    None => (*tile, *tile),
+                })
+            });
+        }
+        Some(removed)
+    }
+
+    /// Inclusive `(min, max)` corners of the bounding box of all tiles.
+    #[must_use]
+    pub fn bounds(&self) -> Option<(IVec2, IVec2)> {
+        self.bounds
+    }
+
+    /// Size of the bounding box of all tiles.
+    #[must_use]
+    pub fn size(&self) -> UVec2 {
+        self.bounds.map_or(UVec2::ZERO, |(min, max)| {
+            (max - min).as_uvec2() + UVec2::ONE
+        })
+    }
+
+    #[must_use]
+    pub fn within_bounds(&self, tile: IVec2) -> bool {
+        self.bounds
+            .is_some_and(|(min, max)| tile.cmpge(min).all() && tile.cmple(max).all())
+    }
+
+    #[must_use]
+    pub fn move_target(&self, pos: IVec2, dir: IVec2) -> Option<(IVec2, &T)> {
+        let target = pos + dir;
+        self.tiles.get(&target).map(|c| (target, c))
+    }
+
+    #[must_use]
+    pub fn neighbours(&self, tile: IVec2) -> Vec<Neigbour<IVec2>> {
+        self.neighbours_impl(DIRS_4, tile)
+    }
+
+    #[must_use]
+    pub fn neighbours_8(&self, tile: IVec2) -> Vec<Neigbour<IVec2>> {
+        self.neighbours_impl(DIRS_8, tile)
+    }
+
+    #[must_use]
+    fn neighbours_impl<const DIRS_LEN: usize>(
+        &self,
+        dirs: [IVec2; DIRS_LEN],
+        tile: IVec2,
+    ) -> Vec<Neigbour<IVec2>> {
+        dirs.iter()
+            .filter_map(|d| {
+                self.move_target(tile, *d)
+                    .map(|(c, _)| Neigbour::new(c, *d))
+            })
+            .collect()
+    }
+
+    /// Prints the bounding box, stored tiles default to `#` and empty space to `.`.
+    pub fn print_debug_map<TFnFormatTile: FnMut(IVec2) -> Option<char>>(
+        &self,
+        mut format_tile: TFnFormatTile,
+    ) {
+        let Some((min, _)) = self.bounds else {
+            return;
+        };
+        let dbg_map = debug_map(min, self.size(), |tile| {
+            if self.tiles.contains_key(&tile) {
+                format_tile(tile).unwrap_or('#')
+            } else {
+                '.'
+            }
+        });
+        println!("{dbg_map}");
+    }
+}
+impl<T: Default> SparseGrid<T> {
+    pub fn from_points<IntoV: Into<IVec2>>(points: impl IntoIterator<Item = IntoV>) -> Self {
+        Self::from_tiles(points.into_iter().map(|p| (p, T::default())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    #[test]
+    #[traced_test]
+    fn bounds() {
+        let mut grid = SparseGrid::<()>::from_points([(-2, 3), (4, -1), (0, 0)]);
+        assert_eq!(grid.bounds(), Some((IVec2::new(-2, -1), IVec2::new(4, 3))));
+        assert_eq!(grid.size(), UVec2::new(7, 5));
+
+        grid.remove(IVec2::new(-2, 3));
+        assert_eq!(grid.bounds(), Some((IVec2::new(0, -1), IVec2::new(4, 0))));
+
+        grid.remove(IVec2::new(4, -1));
+        grid.remove(IVec2::ZERO);
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(grid.size(), UVec2::ZERO);
+    }
+
+    #[test_case(IVec2::ZERO => 2)]
+    #[test_case(IVec2::X => 1)]
+    #[test_case(IVec2::new(5, 5) => 0)]
+    #[traced_test]
+    fn neighbours(tile: IVec2) -> usize {
+        let grid = SparseGrid::<()>::from_points([(-1, 0), (0, -1), (0, 0)]);
+        grid.neighbours(tile).len()
+    }
+
+    #[test]
+    #[traced_test]
+    fn neighbours_8() {
+        let grid = SparseGrid::<()>::from_points([(-1, -1), (1, 1), (0, 0)]);
+        assert_eq!(grid.neighbours_8(IVec2::ZERO).len(), 2);
+    }
+}
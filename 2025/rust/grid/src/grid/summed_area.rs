@@ -0,0 +1,279 @@
+use glam::UVec2;
+use std::ops::{Add, Sub};
+
+use crate::iter::grid_iter;
+
+use super::Grid;
+
+/// 2D prefix sums answering rectangle sums in O(1).
+#[derive(Debug, Clone)]
+pub struct SummedAreaTable<T> {
+    size: UVec2,
+    /// Sums of the rectangles from zero to each tile, one row and column larger than `size`.
+    sums: Vec<T>,
+}
+impl<T: Copy + Default + Add<Output = T> + Sub<Output = T>> SummedAreaTable<T> {
+    pub fn new(size: impl Into<UVec2>, mut value: impl FnMut(UVec2) -> T) -> Self {
+        let size = size.into();
+        let stride = size.x as usize + 1;
+        let mut sums = vec![T::default(); stride * (size.y as usize + 1)];
+        for tile in grid_iter(size) {
+            let (x, y) = (tile.x as usize, tile.y as usize);
+            sums[(y + 1) * stride + x + 1] =
+                value(tile) + sums[y * stride + x + 1] + sums[(y + 1) * stride + x]
+                    - sums[y * stride + x];
+        }
+        Self { size, sums }
+    }
+
+    /// Table of row-major `values` (e.g. from [`DifferenceGrid::into_values`]).
+    #[must_use]
+    pub fn from_values(size: impl Into<UVec2>, values: &[T]) -> Self {
+        let size = size.into();
+        Self::new(size, |tile| values[(tile.y * size.x + tile.x) as usize])
+    }
+
+    #[must_use]
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// Sum of the rectangle between the corners (inclusive), clipped to the table.
+    #[must_use]
+    pub fn sum(&self, a: impl Into<UVec2>, b: impl Into<UVec2>) -> T {
+        let (a, b) = (a.into(), b.into());
+        let min = a.min(b).min(self.size);
+        let max = (a.max(b) + UVec2::ONE).min(self.size);
+        if min.cmpge(max).any() {
+            return T::default();
+        }
+        self.at(max) + self.at(min)
+            - self.at(UVec2::new(min.x, max.y))
+            - self.at(UVec2::new(max.x, min.y))
+    }
+
+    #[must_use]
+    pub fn total(&self) -> T {
+        self.at(self.size)
+    }
+
+    /// Sums of the top-left, top-right, bottom-left and bottom-right quadrants.
+    ///
+    /// For odd sizes the middle row or column belongs to no quadrant.
+    #[must_use]
+    pub fn quadrants(&self) -> [T; 4] {
+        let half = self.size / 2;
+        let far = self.size - half;
+        let last = self.size.saturating_sub(UVec2::ONE);
+        let [left, top] = [half.x, half.y].map(|h| h.checked_sub(1));
+        let tl = match (left, top) {
+            (Some(x), Some(y)) => self.sum(UVec2::ZERO, (x, y)),
+            _ => T::default(),
+        };
+        let tr = top.map_or(T::default(), |y| self.sum((far.x, 0), (last.x, y)));
+        let bl = left.map_or(T::default(), |x| self.sum((0, far.y), (x, last.y)));
+        let br = if half.cmpgt(UVec2::ZERO).all() {
+            self.sum(far, last)
+        } else {
+            T::default()
+        };
+        [tl, tr, bl, br]
+    }
+
+    /// Sum of every cell of a `parts.x` by `parts.y` partition, in row order.
+    ///
+    /// Cells have equal size, leftover rows and columns go to the last cells.
+    #[must_use]
+    pub fn partition(&self, parts: impl Into<UVec2>) -> Vec<T> {
+        let parts = parts.into().max(UVec2::ONE);
+        let cell = self.size / parts;
+        grid_iter(parts)
+            .map(|part| {
+                let min = part * cell;
+                let end = UVec2::select(part.cmpeq(parts - UVec2::ONE), self.size, min + cell);
+                if min.cmpge(end).any() {
+                    T::default()
+                } else {
+                    self.sum(min, end - UVec2::ONE)
+                }
+            })
+            .collect()
+    }
+
+    fn at(&self, corner: UVec2) -> T {
+        self.sums[corner.y as usize * (self.size.x as usize + 1) + corner.x as usize]
+    }
+}
+impl SummedAreaTable<u32> {
+    /// Counts of points per rectangle, points outside of `size` are ignored.
+    pub fn from_points(size: impl Into<UVec2>, points: impl IntoIterator<Item = UVec2>) -> Self {
+        let size = size.into();
+        let mut counts = vec![0; size.element_product() as usize];
+        for p in points {
+            if p.cmplt(size).all() {
+                counts[(p.y * size.x + p.x) as usize] += 1;
+            }
+        }
+        Self::from_values(size, &counts)
+    }
+}
+
+/// Bulk rectangle additions in O(1) each, materialized once at the end.
+///
+/// Corner additions and subtractions are kept apart so unsigned values never go below zero.
+#[derive(Debug, Clone)]
+pub struct DifferenceGrid<T> {
+    size: UVec2,
+    added: Vec<T>,
+    removed: Vec<T>,
+}
+impl<T: Copy + Default + Add<Output = T> + Sub<Output = T>> DifferenceGrid<T> {
+    pub fn new(size: impl Into<UVec2>) -> Self {
+        let size = size.into();
+        let corners = vec![T::default(); (size.x as usize + 1) * (size.y as usize + 1)];
+        Self {
+            size,
+            added: corners.clone(),
+            removed: corners,
+        }
+    }
+
+    /// Adds `value` to every tile of the rectangle between the corners (inclusive),
+    /// clipped to the grid.
+    pub fn add(&mut self, a: impl Into<UVec2>, b: impl Into<UVec2>, value: T) {
+        let (a, b) = (a.into(), b.into());
+        let min = a.min(b).min(self.size);
+        let max = (a.max(b) + UVec2::ONE).min(self.size);
+        if min.cmpge(max).any() {
+            return;
+        }
+        let stride = self.size.x as usize + 1;
+        let i = |c: UVec2| c.y as usize * stride + c.x as usize;
+        self.added[i(min)] = self.added[i(min)] + value;
+        self.added[i(max)] = self.added[i(max)] + value;
+        let (top_right, bottom_left) = (UVec2::new(max.x, min.y), UVec2::new(min.x, max.y));
+        self.removed[i(top_right)] = self.removed[i(top_right)] + value;
+        self.removed[i(bottom_left)] = self.removed[i(bottom_left)] + value;
+    }
+
+    /// Final tile values in row order.
+    #[must_use]
+    pub fn into_values(self) -> Vec<T> {
+        let stride = self.size.x as usize + 1;
+        let prefix = |corners: &[T]| {
+            SummedAreaTable::new(self.size, |t| corners[t.y as usize * stride + t.x as usize])
+        };
+        let (added, removed) = (prefix(&self.added), prefix(&self.removed));
+        grid_iter(self.size)
+            .map(|t| added.sum(UVec2::ZERO, t) - removed.sum(UVec2::ZERO, t))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn into_summed_area_table(self) -> SummedAreaTable<T> {
+        let size = self.size;
+        SummedAreaTable::from_values(size, &self.into_values())
+    }
+}
+
+impl<T> Grid<T> {
+    /// Prefix sums of `value` over the walkable tiles, obstacles count as the default value.
+    pub fn summed_area_table<V: Copy + Default + Add<Output = V> + Sub<Output = V>>(
+        &self,
+        mut value: impl FnMut(UVec2, &T) -> V,
+    ) -> SummedAreaTable<V> {
+        SummedAreaTable::new(self.size, |tile| {
+            self.walkable_tiles
+                .get(&tile)
+                .map_or(V::default(), |v| value(tile, v))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::IVec2;
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    const ROBOTS: [((i32, i32), (i32, i32)); 12] = [
+        ((0, 4), (3, -3)),
+        ((6, 3), (-1, -3)),
+        ((10, 3), (-1, 2)),
+        ((2, 0), (2, -1)),
+        ((0, 0), (1, 3)),
+        ((3, 0), (-2, -2)),
+        ((7, 6), (-1, -3)),
+        ((3, 0), (-1, -2)),
+        ((9, 3), (2, 3)),
+        ((7, 3), (-1, 2)),
+        ((2, 4), (2, -3)),
+        ((9, 5), (-3, -3)),
+    ];
+
+    #[test]
+    #[traced_test]
+    fn robot_quadrants() {
+        let size = IVec2::new(11, 7);
+        let robots = ROBOTS.iter().map(|(p, v)| {
+            (IVec2::from(*p) + IVec2::from(*v) * 100)
+                .rem_euclid(size)
+                .as_uvec2()
+        });
+        let table = SummedAreaTable::from_points(size.as_uvec2(), robots);
+        assert_eq!(table.total(), 12);
+        assert_eq!(table.quadrants(), [1, 3, 4, 1]);
+        assert_eq!(table.quadrants().iter().product::<u32>(), 12);
+    }
+
+    #[test_case((0, 0), (2, 2) => 9)]
+    #[test_case((3, 3), (1, 1) => 9)]
+    #[test_case((1, 0), (1, 3) => 4)]
+    #[test_case((2, 2), (10, 10) => 4)]
+    #[test_case((5, 5), (10, 10) => 0)]
+    #[traced_test]
+    fn sum(a: (u32, u32), b: (u32, u32)) -> u32 {
+        let grid = Grid::<()>::from_size((4, 4));
+        grid.summed_area_table(|_, ()| 1u32).sum(a, b)
+    }
+
+    #[test]
+    #[traced_test]
+    fn partition() {
+        let table = SummedAreaTable::new((5, 4), |t| t.x + t.y * 10);
+        assert_eq!(table.partition((2, 2)).iter().sum::<u32>(), table.total());
+        assert_eq!(
+            table.partition((2, 1)),
+            [table.sum((0, 0), (1, 3)), table.sum((2, 0), (4, 3))]
+        );
+    }
+
+    #[test]
+    #[traced_test]
+    fn difference_grid() {
+        let mut diff = DifferenceGrid::new((4, 3));
+        diff.add((0, 0), (1, 1), 1i32);
+        diff.add((1, 1), (9, 9), 2);
+        diff.add((3, 0), (3, 0), -5);
+        assert_eq!(
+            diff.clone().into_values(),
+            [1, 1, 0, -5, 1, 3, 2, 2, 0, 2, 2, 2]
+        );
+        assert_eq!(diff.into_summed_area_table().total(), 11);
+    }
+
+    #[test]
+    #[traced_test]
+    fn difference_grid_unsigned() {
+        let mut diff = DifferenceGrid::new((4, 3));
+        diff.add((0, 0), (1, 1), 1u32);
+        diff.add((1, 1), (9, 9), 2);
+        assert_eq!(diff.into_values(), [1, 1, 0, 0, 1, 3, 2, 2, 0, 2, 2, 2]);
+
+        let mut diff = DifferenceGrid::new((2, 2));
+        diff.add((1, 0), (1, 1), u64::MAX / 2);
+        diff.add((0, 1), (1, 1), 1u64);
+        assert_eq!(diff.into_values(), [0, u64::MAX / 2, 1, u64::MAX / 2 + 1]);
+    }
+}
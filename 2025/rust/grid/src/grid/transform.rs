@@ -0,0 +1,313 @@
+use glam::{IVec2, UVec2, Vec2Swizzles};
+use std::collections::HashMap;
+
+use crate::iter::grid_iter;
+
+use super::Grid;
+
+/// One of the 8 ways to lay down a grid: `rotations` quarter turns clockwise,
+/// applied after an optional horizontal flip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    pub flipped: bool,
+    pub rotations: u8,
+}
+impl Orientation {
+    pub const IDENTITY: Orientation = Orientation {
+        flipped: false,
+        rotations: 0,
+    };
+
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [false, true]
+            .into_iter()
+            .flat_map(|flipped| (0..4).map(move |rotations| Orientation { flipped, rotations }))
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    #[must_use]
+    pub fn rotate_cw(&self) -> Self {
+        let h = self.size.y;
+        self.remap(self.size.yx(), |t| UVec2::new(h - 1 - t.y, t.x))
+    }
+
+    #[must_use]
+    pub fn rotate_ccw(&self) -> Self {
+        let w = self.size.x;
+        self.remap(self.size.yx(), |t| UVec2::new(t.y, w - 1 - t.x))
+    }
+
+    /// Mirrors left to right.
+    #[must_use]
+    pub fn flip_horizontal(&self) -> Self {
+        let w = self.size.x;
+        self.remap(self.size, |t| UVec2::new(w - 1 - t.x, t.y))
+    }
+
+    /// Mirrors top to bottom.
+    #[must_use]
+    pub fn flip_vertical(&self) -> Self {
+        let h = self.size.y;
+        self.remap(self.size, |t| UVec2::new(t.x, h - 1 - t.y))
+    }
+
+    #[must_use]
+    pub fn transpose(&self) -> Self {
+        self.remap(self.size.yx(), Vec2Swizzles::yx)
+    }
+
+    #[must_use]
+    pub fn oriented(&self, orientation: Orientation) -> Self {
+        let mut grid = if orientation.flipped {
+            self.flip_horizontal()
+        } else {
+            self.remap(self.size, |t| t)
+        };
+        for _ in 0..orientation.rotations % 4 {
+            grid = grid.rotate_cw();
+        }
+        grid
+    }
+
+    /// Rectangle starting at `min`, clipped to the grid, with tiles shifted to start at zero.
+    #[must_use]
+    pub fn sub_grid(&self, min: impl Into<UVec2>, size: impl Into<UVec2>) -> Self {
+        let min = min.into().min(self.size);
+        let size = size.into().min(self.size - min);
+        let walkable_tiles = grid_iter(size)
+            .filter_map(|t| self.walkable_tiles.get(&(t + min)).map(|v| (t, v.clone())))
+            .collect();
+        Self {
+            size,
+            walkable_tiles,
+            topology: self.topology,
+        }
+    }
+
+    /// Grid repeated `repeat.x` times horizontally and `repeat.y` times vertically.
+    #[must_use]
+    pub fn tiled(&self, repeat: impl Into<UVec2>) -> Self {
+        let repeat = repeat.into();
+        let walkable_tiles = grid_iter(repeat)
+            .flat_map(|offset| {
+                self.walkable_tiles
+                    .iter()
+                    .map(move |(t, v)| (*t + offset * self.size, v.clone()))
+            })
+            .collect();
+        Self {
+            size: self.size * repeat,
+            walkable_tiles,
+            topology: self.topology,
+        }
+    }
+
+    fn remap(&self, size: UVec2, map: impl Fn(UVec2) -> UVec2) -> Self {
+        let walkable_tiles = self
+            .walkable_tiles
+            .iter()
+            .map(|(t, v)| (map(*t), v.clone()))
+            .collect::<HashMap<_, _>>();
+        Self {
+            size,
+            walkable_tiles,
+            topology: self.topology,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Tiles of row `y` from left to right, `None` for obstacles.
+    pub fn row(&self, y: u32) -> impl Iterator<Item = (UVec2, Option<&T>)> + '_ {
+        self.line(IVec2::new(0, y as _), IVec2::X)
+    }
+
+    /// Tiles of column `x` from top to bottom, `None` for obstacles.
+    pub fn column(&self, x: u32) -> impl Iterator<Item = (UVec2, Option<&T>)> + '_ {
+        self.line(IVec2::new(x as _, 0), IVec2::Y)
+    }
+
+    /// Every row, see [`Grid::row`].
+    pub fn rows(
+        &self,
+    ) -> impl Iterator<Item = impl Iterator<Item = (UVec2, Option<&T>)> + '_> + '_ {
+        (0..self.size.y).map(|y| self.row(y))
+    }
+
+    /// Every column, see [`Grid::column`].
+    pub fn columns(
+        &self,
+    ) -> impl Iterator<Item = impl Iterator<Item = (UVec2, Option<&T>)> + '_> + '_ {
+        (0..self.size.x).map(|x| self.column(x))
+    }
+
+    /// Lines going down and right, starting on the left column or the top row.
+    pub fn diagonals(
+        &self,
+    ) -> impl Iterator<Item = impl Iterator<Item = (UVec2, Option<&T>)> + '_> + '_ {
+        let starts = (0..self.size.y as i32)
+            .rev()
+            .map(|y| IVec2::new(0, y))
+            .chain((1..self.size.x as i32).map(|x| IVec2::new(x, 0)));
+        starts.map(|start| self.line(start, IVec2::ONE))
+    }
+
+    /// Lines going down and left, starting on the top row or the right column.
+    pub fn anti_diagonals(
+        &self,
+    ) -> impl Iterator<Item = impl Iterator<Item = (UVec2, Option<&T>)> + '_> + '_ {
+        let right = self.size.x as i32 - 1;
+        let starts = (0..self.size.x as i32)
+            .map(|x| IVec2::new(x, 0))
+            .chain((1..self.size.y as i32).map(move |y| IVec2::new(right, y)));
+        starts.map(|start| self.line(start, IVec2::new(-1, 1)))
+    }
+
+    fn line(&self, start: IVec2, step: IVec2) -> impl Iterator<Item = (UVec2, Option<&T>)> + '_ {
+        (0..)
+            .map(move |i| start + step * i)
+            .take_while(|t| self.within_bounds(*t))
+            .map(|t| {
+                let t = t.as_uvec2();
+                (t, self.walkable_tiles.get(&t))
+            })
+    }
+}
+
+impl<T: Clone + PartialEq> Grid<T> {
+    /// Top-left tiles where `pattern` matches in any orientation, orientations producing
+    /// the same pattern are reported once.
+    ///
+    /// Obstacles in the pattern match anything.
+    #[must_use]
+    pub fn find_pattern(&self, pattern: &Grid<T>) -> Vec<(UVec2, Orientation)> {
+        let mut oriented: Vec<(Orientation, Grid<T>)> = Vec::new();
+        for orientation in Orientation::iter() {
+            let candidate = pattern.oriented(orientation);
+            if !oriented.iter().any(|(_, p)| {
+                p.size == candidate.size && p.walkable_tiles == candidate.walkable_tiles
+            }) {
+                oriented.push((orientation, candidate));
+            }
+        }
+        oriented
+            .iter()
+            .flat_map(|(orientation, pattern)| {
+                self.find_pattern_at(pattern)
+                    .map(move |tile| (tile, *orientation))
+            })
+            .collect()
+    }
+
+    fn find_pattern_at<'a>(&'a self, pattern: &'a Grid<T>) -> impl Iterator<Item = UVec2> + 'a {
+        let positions = if pattern.size.cmpgt(self.size).any() {
+            UVec2::ZERO
+        } else {
+            self.size + UVec2::ONE - pattern.size
+        };
+        grid_iter(positions).filter(|offset| {
+            pattern
+                .walkable_tiles
+                .iter()
+                .all(|(t, v)| self.walkable_tiles.get(&(*t + *offset)) == Some(v))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::parse_grid_with;
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    const WORD_SEARCH: &str = "MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+
+    /// `.` marks tiles that are not part of the word search or pattern.
+    fn letter(c: char) -> Option<char> {
+        (c != '.').then_some(c)
+    }
+
+    fn lines(grid: &Grid<char>) -> Vec<String> {
+        let collect = |line: &mut dyn Iterator<Item = (UVec2, Option<&char>)>| {
+            line.map(|(_, c)| c.copied().unwrap_or('.'))
+                .collect::<String>()
+        };
+        let mut lines = Vec::new();
+        lines.extend(grid.rows().map(|mut l| collect(&mut l)));
+        lines.extend(grid.columns().map(|mut l| collect(&mut l)));
+        lines.extend(grid.diagonals().map(|mut l| collect(&mut l)));
+        lines.extend(grid.anti_diagonals().map(|mut l| collect(&mut l)));
+        lines
+    }
+
+    #[test]
+    #[traced_test]
+    fn transforms() {
+        let grid = parse_grid_with("ab\ncd\nef", letter);
+        assert_eq!(lines(&grid.rotate_cw())[..2], ["eca", "fdb"]);
+        assert_eq!(lines(&grid.rotate_ccw())[..2], ["bdf", "ace"]);
+        assert_eq!(lines(&grid.flip_horizontal())[..3], ["ba", "dc", "fe"]);
+        assert_eq!(lines(&grid.flip_vertical())[..3], ["ef", "cd", "ab"]);
+        assert_eq!(lines(&grid.transpose())[..2], ["ace", "bdf"]);
+        let oriented = grid.oriented(Orientation {
+            flipped: true,
+            rotations: 2,
+        });
+        assert_eq!(lines(&oriented)[..3], lines(&grid.flip_vertical())[..3]);
+    }
+
+    #[test]
+    #[traced_test]
+    fn sub_grid_and_tiling() {
+        let grid = parse_grid_with("abc\nd.f", letter);
+        let sub = grid.sub_grid((1, 0), (5, 5));
+        assert_eq!(sub.size(), UVec2::new(2, 2));
+        assert_eq!(lines(&sub)[..2], ["bc", ".f"]);
+        let tiled = grid.tiled((2, 2));
+        assert_eq!(tiled.size(), UVec2::new(6, 4));
+        assert_eq!(lines(&tiled)[..4], ["abcabc", "d.fd.f", "abcabc", "d.fd.f"]);
+    }
+
+    #[test]
+    #[traced_test]
+    fn lines_keep_obstacles() {
+        let grid = parse_grid_with("XM.AS\n.....", letter);
+        assert_eq!(
+            grid.row(0).map(|(_, c)| c.copied()).collect::<Vec<_>>(),
+            [Some('X'), Some('M'), None, Some('A'), Some('S')]
+        );
+        assert_eq!(lines(&grid)[..2], ["XM.AS", "....."]);
+        assert!(!lines(&grid).iter().any(|line| line.contains("XMAS")));
+    }
+
+    #[test_case("XMAS" => 18)]
+    #[traced_test]
+    fn word_search(word: &str) -> usize {
+        let reversed = word.chars().rev().collect::<String>();
+        lines(&parse_grid_with(WORD_SEARCH, letter))
+            .iter()
+            .map(|line| line.matches(word).count() + line.matches(&reversed).count())
+            .sum()
+    }
+
+    #[test_case("M.S\n.A.\nM.S" => 9)]
+    #[test_case("XMAS" => 8)]
+    #[test_case("SAMX" => 8)]
+    #[traced_test]
+    fn find_pattern(pattern: &str) -> usize {
+        parse_grid_with(WORD_SEARCH, letter)
+            .find_pattern(&parse_grid_with(pattern, letter))
+            .len()
+    }
+}
@@ -0,0 +1,379 @@
+use glam::{IVec2, IVec3};
+use std::{
+    collections::HashMap,
+    ops::{Add, AddAssign, Mul, Sub},
+    str::FromStr,
+};
+
+use crate::{
+    dir::ParseDirError,
+    grid::{find_state_path_astar, find_state_path_bfs, Neigbour, NodePath},
+};
+
+/// Axial coordinates of a pointy-top hexagon (`s = -q - r` completes the cube coordinates).
+///
+/// `q` grows to the east and `r` to the south-east.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+impl Hex {
+    pub const ZERO: Hex = Hex { q: 0, r: 0 };
+
+    #[must_use]
+    pub const fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    #[must_use]
+    pub const fn s(self) -> i32 {
+        -self.q - self.r
+    }
+
+    #[must_use]
+    pub const fn cube(self) -> IVec3 {
+        IVec3::new(self.q, self.r, self.s())
+    }
+
+    /// `None` unless the cube coordinates sum up to zero.
+    #[must_use]
+    pub fn from_cube(cube: IVec3) -> Option<Self> {
+        (cube.element_sum() == 0).then_some(Self::new(cube.x, cube.y))
+    }
+
+    #[must_use]
+    pub fn neighbour(self, dir: HexDir) -> Self {
+        self + dir.to_hex()
+    }
+
+    #[must_use]
+    pub fn neighbours(self) -> [Hex; 6] {
+        HexDir::ALL.map(|dir| self.neighbour(dir))
+    }
+
+    /// Number of steps between the hexagons.
+    #[must_use]
+    pub fn distance(self, other: Hex) -> u32 {
+        ((self - other).cube().abs().element_sum() / 2) as u32
+    }
+
+    /// Hexagons exactly `radius` steps away, starting west of the center and going clockwise.
+    pub fn ring(self, radius: u32) -> impl Iterator<Item = Hex> {
+        let start = self + HexDir::West.to_hex() * radius as i32;
+        let steps = if radius == 0 { 1 } else { 6 * radius };
+        // Walking the ring turns one direction clockwise every `radius` steps.
+        let dirs = [
+            HexDir::NorthEast,
+            HexDir::East,
+            HexDir::SouthEast,
+            HexDir::SouthWest,
+            HexDir::West,
+            HexDir::NorthWest,
+        ];
+        (0..steps).scan(start, move |hex, i| {
+            let current = *hex;
+            if let Some(side) = i.checked_div(radius) {
+                *hex = hex.neighbour(dirs[side as usize]);
+            }
+            Some(current)
+        })
+    }
+
+    /// Hexagons within `radius` steps, ring by ring from the center outwards.
+    pub fn spiral(self, radius: u32) -> impl Iterator<Item = Hex> {
+        (0..=radius).flat_map(move |r| self.ring(r))
+    }
+}
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, rhs: Hex) -> Hex {
+        Hex::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+impl AddAssign for Hex {
+    fn add_assign(&mut self, rhs: Hex) {
+        *self = *self + rhs;
+    }
+}
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, rhs: Hex) -> Hex {
+        Hex::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+impl Mul<i32> for Hex {
+    type Output = Hex;
+
+    fn mul(self, rhs: i32) -> Hex {
+        Hex::new(self.q * rhs, self.r * rhs)
+    }
+}
+impl From<Hex> for IVec2 {
+    fn from(hex: Hex) -> Self {
+        IVec2::new(hex.q, hex.r)
+    }
+}
+impl From<IVec2> for Hex {
+    fn from(vec: IVec2) -> Self {
+        Hex::new(vec.x, vec.y)
+    }
+}
+
+/// Step between neighbouring pointy-top hexagons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HexDir {
+    East,
+    SouthEast,
+    SouthWest,
+    West,
+    NorthWest,
+    NorthEast,
+}
+impl HexDir {
+    /// Clockwise from east.
+    pub const ALL: [HexDir; 6] = [
+        HexDir::East,
+        HexDir::SouthEast,
+        HexDir::SouthWest,
+        HexDir::West,
+        HexDir::NorthWest,
+        HexDir::NorthEast,
+    ];
+
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Self::ALL.into_iter()
+    }
+
+    #[must_use]
+    pub const fn to_hex(self) -> Hex {
+        match self {
+            HexDir::East => Hex::new(1, 0),
+            HexDir::SouthEast => Hex::new(0, 1),
+            HexDir::SouthWest => Hex::new(-1, 1),
+            HexDir::West => Hex::new(-1, 0),
+            HexDir::NorthWest => Hex::new(0, -1),
+            HexDir::NorthEast => Hex::new(1, -1),
+        }
+    }
+
+    #[must_use]
+    pub const fn rotate_cw(self) -> Self {
+        Self::ALL[(self as usize + 1) % 6]
+    }
+
+    #[must_use]
+    pub const fn rotate_ccw(self) -> Self {
+        Self::ALL[(self as usize + 5) % 6]
+    }
+
+    #[must_use]
+    pub const fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 3) % 6]
+    }
+
+    /// Parses steps written back to back (`esenee`) or separated by commas or whitespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for anything but `e`, `se`, `sw`, `w`, `nw` and `ne`.
+    pub fn parse_steps(steps: &str) -> Result<Vec<HexDir>, ParseDirError> {
+        let mut dirs = Vec::new();
+        let separator = |c: char| c == ',' || c.is_whitespace();
+        let mut rest = steps.trim_start_matches(separator);
+        while !rest.is_empty() {
+            let len = if rest.starts_with(['n', 's']) { 2 } else { 1 };
+            let step = rest.get(..len).unwrap_or(rest);
+            dirs.push(step.parse()?);
+            rest = rest[step.len()..].trim_start_matches(separator);
+        }
+        Ok(dirs)
+    }
+}
+impl FromStr for HexDir {
+    type Err = ParseDirError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "e" => Ok(HexDir::East),
+            "se" => Ok(HexDir::SouthEast),
+            "sw" => Ok(HexDir::SouthWest),
+            "w" => Ok(HexDir::West),
+            "nw" => Ok(HexDir::NorthWest),
+            "ne" => Ok(HexDir::NorthEast),
+            _ => Err(ParseDirError::InvalidStr(s.to_string())),
+        }
+    }
+}
+
+/// Unbounded hexagonal grid, only the stored hexagons are walkable.
+#[derive(Debug, Clone)]
+pub struct HexGrid<T = ()> {
+    tiles: HashMap<Hex, T>,
+}
+impl<T> Default for HexGrid<T> {
+    fn default() -> Self {
+        Self {
+            tiles: HashMap::new(),
+        }
+    }
+}
+impl<T> HexGrid<T> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_tiles(tiles: impl IntoIterator<Item = (Hex, T)>) -> Self {
+        Self {
+            tiles: tiles.into_iter().collect(),
+        }
+    }
+
+    #[must_use]
+    pub fn tiles(&self) -> &HashMap<Hex, T> {
+        &self.tiles
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    #[must_use]
+    pub fn get(&self, hex: Hex) -> Option<&T> {
+        self.tiles.get(&hex)
+    }
+
+    #[must_use]
+    pub fn get_mut(&mut self, hex: Hex) -> Option<&mut T> {
+        self.tiles.get_mut(&hex)
+    }
+
+    #[must_use]
+    pub fn contains(&self, hex: Hex) -> bool {
+        self.tiles.contains_key(&hex)
+    }
+
+    pub fn insert(&mut self, hex: Hex, val: T) -> Option<T> {
+        self.tiles.insert(hex, val)
+    }
+
+    pub fn remove(&mut self, hex: Hex) -> Option<T> {
+        self.tiles.remove(&hex)
+    }
+
+    /// Stored neighbours, the direction is the axial step as `(q, r)`.
+    #[must_use]
+    pub fn neighbours(&self, hex: Hex) -> Vec<Neigbour<Hex>> {
+        HexDir::iter()
+            .map(|dir| Neigbour::new(hex.neighbour(dir), dir.to_hex()))
+            .filter(|n| self.tiles.contains_key(&n.tile))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn find_path_bfs(&self, start: Hex, end: Hex) -> Option<NodePath<Hex>> {
+        find_state_path_bfs(
+            &start,
+            |hex| self.neighbours(*hex).into_iter().map(|n| n.tile),
+            |hex| *hex == end,
+        )
+    }
+
+    #[must_use]
+    pub fn find_path_astar(&self, start: Hex, end: Hex) -> Option<NodePath<Hex>> {
+        find_state_path_astar(
+            &start,
+            |hex| self.neighbours(*hex).into_iter().map(|n| (n.tile, 1)),
+            |hex| hex.distance(end),
+            |hex| *hex == end,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    #[test_case("esew" => Hex::new(0, 1))]
+    #[test_case("nwwswee" => Hex::ZERO)]
+    #[test_case("ne,ne,ne" => Hex::new(3, -3))]
+    #[test_case("sw sw w" => Hex::new(-3, 2))]
+    #[test_case("ne,\r\nsw\t" => Hex::ZERO)]
+    #[traced_test]
+    fn walk(steps: &str) -> Hex {
+        HexDir::parse_steps(steps)
+            .unwrap()
+            .into_iter()
+            .fold(Hex::ZERO, Hex::neighbour)
+    }
+
+    #[test_case("nesx")]
+    #[test_case("n")]
+    #[traced_test]
+    fn invalid_steps(steps: &str) {
+        assert!(HexDir::parse_steps(steps).is_err());
+    }
+
+    #[test]
+    #[traced_test]
+    fn directions() {
+        for dir in HexDir::iter() {
+            assert_eq!(dir.rotate_cw().rotate_ccw(), dir);
+            assert_eq!(dir.to_hex() + dir.opposite().to_hex(), Hex::ZERO);
+            assert_eq!(Hex::ZERO.neighbour(dir).distance(Hex::ZERO), 1);
+        }
+        assert_eq!(Hex::from_cube(IVec3::new(1, -1, 0)), Some(Hex::new(1, -1)));
+        assert_eq!(Hex::from_cube(IVec3::ONE), None);
+    }
+
+    #[test_case(0 => 1)]
+    #[test_case(1 => 6)]
+    #[test_case(3 => 18)]
+    #[traced_test]
+    fn ring(radius: u32) -> usize {
+        let center = Hex::new(2, -5);
+        let ring: HashSet<_> = center.ring(radius).collect();
+        assert!(ring.iter().all(|hex| hex.distance(center) == radius));
+        ring.len()
+    }
+
+    #[test]
+    #[traced_test]
+    fn spiral() {
+        let spiral: Vec<_> = Hex::ZERO.spiral(2).collect();
+        assert_eq!(spiral.len(), 19);
+        assert_eq!(spiral[0], Hex::ZERO);
+        assert_eq!(spiral.iter().collect::<HashSet<_>>().len(), 19);
+    }
+
+    #[test]
+    #[traced_test]
+    fn paths() {
+        let wall: HashSet<_> = Hex::ZERO.ring(2).filter(|hex| hex.q != 2).collect();
+        let grid = HexGrid::from_tiles(
+            Hex::ZERO
+                .spiral(4)
+                .filter(|hex| !wall.contains(hex))
+                .map(|hex| (hex, ())),
+        );
+        let end = Hex::new(-3, 0);
+        let bfs = grid.find_path_bfs(Hex::ZERO, end).unwrap();
+        let astar = grid.find_path_astar(Hex::ZERO, end).unwrap();
+        assert_eq!(bfs.cost, astar.cost);
+        assert!(bfs.cost > Hex::ZERO.distance(end));
+        assert_eq!(grid.neighbours(Hex::ZERO).len(), 6);
+        assert_eq!(grid.neighbours(Hex::new(1, 0)).len(), 5);
+    }
+}
@@ -1,4 +1,4 @@
-use glam::UVec2;
+use glam::{IVec2, UVec2, UVec3};
 
 pub struct GridIterator {
     grid_size: UVec2,
@@ -8,7 +8,7 @@ impl Iterator for GridIterator {
     type Item = UVec2;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.index.y >= self.grid_size.y {
+        if self.index.y >= self.grid_size.y || self.grid_size.x == 0 {
             None
         } else {
             let next = self.index;
@@ -28,11 +28,53 @@ pub fn grid_iter(grid_size: impl Into<UVec2>) -> GridIterator {
     }
 }
 
+/// Every tile of a 3D grid, x first, then y, then z.
+pub fn grid_iter_3(grid_size: impl Into<UVec3>) -> impl Iterator<Item = UVec3> {
+    let size = grid_size.into();
+    (0..size.z).flat_map(move |z| {
+        (0..size.y).flat_map(move |y| (0..size.x).map(move |x| UVec3::new(x, y, z)))
+    })
+}
+
+/// Tiles within the manhattan `radius` of `center` (including `center`) in row order.
+pub fn manhattan_iter(center: IVec2, radius: u32) -> impl Iterator<Item = IVec2> {
+    let radius = radius as i32;
+    (-radius..=radius).flat_map(move |y| {
+        let width = radius - y.abs();
+        (-width..=width).map(move |x| center + IVec2::new(x, y))
+    })
+}
+
+/// Bresenham line from `a` to `b`, both ends included.
+pub fn line_iter(a: IVec2, b: IVec2) -> impl Iterator<Item = IVec2> {
+    let delta = (b - a).abs() * IVec2::new(1, -1);
+    let step = (b - a).signum();
+    let mut error = delta.x + delta.y;
+    let mut tile = Some(a);
+    std::iter::from_fn(move || {
+        let current = tile?;
+        tile = (current != b).then(|| {
+            let mut next = current;
+            let doubled = 2 * error;
+            if doubled >= delta.y {
+                error += delta.y;
+                next.x += step.x;
+            }
+            if doubled <= delta.x {
+                error += delta.x;
+                next.y += step.y;
+            }
+            next
+        });
+        Some(current)
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use glam::UVec2;
+    use glam::{IVec2, UVec2};
 
-    use super::grid_iter;
+    use super::{grid_iter, line_iter, manhattan_iter};
 
     #[test]
     fn iter() {
@@ -59,5 +101,28 @@ mod tests {
             ]
             .map(Into::into)
         );
+        assert_eq!(grid_iter((0, 3)).count(), 0);
+    }
+
+    #[test]
+    fn manhattan() {
+        let tiles: Vec<_> = manhattan_iter(IVec2::ONE, 1).collect();
+        assert_eq!(
+            tiles,
+            [(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)].map(Into::into)
+        );
+        assert_eq!(manhattan_iter(IVec2::ZERO, 20).count(), 841);
+    }
+
+    #[test]
+    fn line() {
+        let tiles: Vec<_> = line_iter(IVec2::ZERO, IVec2::new(4, 2)).collect();
+        assert_eq!(
+            tiles,
+            [(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)].map(Into::into)
+        );
+        let tiles: Vec<_> = line_iter(IVec2::new(1, 3), IVec2::new(1, 0)).collect();
+        assert_eq!(tiles, [(1, 3), (1, 2), (1, 1), (1, 0)].map(Into::into));
+        assert_eq!(line_iter(IVec2::ONE, IVec2::ONE).count(), 1);
     }
 }
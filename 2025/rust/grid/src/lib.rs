@@ -1,7 +1,11 @@
+pub mod compress;
+pub mod cycle;
 pub mod dir;
 mod ext;
 mod grid;
+pub mod hex;
 pub mod iter;
+pub mod testing;
 
 pub use ext::*;
 pub use grid::*;
@@ -9,10 +13,13 @@ pub use grid::*;
 pub mod prelude {
     pub use glam::{IVec2, UVec2};
 
+    pub use crate::compress::*;
+    pub use crate::cycle::*;
     pub use crate::dir::*;
     pub use crate::ext::*;
     pub use crate::grid::builder::*;
     pub use crate::grid::*;
+    pub use crate::hex::*;
     pub use crate::iter::*;
 
     pub mod pathfinding {
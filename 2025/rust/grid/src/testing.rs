@@ -0,0 +1,205 @@
+use glam::UVec2;
+use std::fmt::Write as _;
+
+use crate::Grid;
+
+/// Normalizes an inline text grid written in a test.
+///
+/// Leading and trailing blank lines, the indentation common to all lines and trailing
+/// whitespace are removed, so expected grids can be indented along with the test code.
+#[must_use]
+pub fn inline_grid(text: &str) -> String {
+    let lines: Vec<_> = text.lines().map(str::trim_end).collect();
+    let first = lines
+        .iter()
+        .position(|l| !l.is_empty())
+        .unwrap_or(lines.len());
+    let last = lines
+        .iter()
+        .rposition(|l| !l.is_empty())
+        .map_or(first, |i| i + 1);
+    let lines = &lines[first..last];
+    let indent = lines
+        .iter()
+        .filter(|l| !l.is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|l| l.get(indent..).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Grid of an inline text grid (see [`inline_grid`]), `#` tiles are obstacles and every
+/// other char is walkable.
+#[must_use]
+pub fn parse_grid(text: &str) -> Grid<char> {
+    parse_grid_with(text, |c| (c != '#').then_some(c))
+}
+
+/// Grid of an inline text grid (see [`inline_grid`]), `classify` returns `None` for
+/// obstacles. Lines shorter than the widest one are padded with obstacles.
+pub fn parse_grid_with<T>(text: &str, mut classify: impl FnMut(char) -> Option<T>) -> Grid<T> {
+    let text = inline_grid(text);
+    let width = text.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+    let tiles: Vec<_> = text
+        .lines()
+        .enumerate()
+        .flat_map(|(y, line)| {
+            line.chars()
+                .enumerate()
+                .map(move |(x, c)| (UVec2::new(x as _, y as _), c))
+        })
+        .filter_map(|(tile, c)| classify(c).map(|value| (tile, value)))
+        .collect();
+    Grid::from_walkable_tiles(
+        tiles.into_iter(),
+        (width as u32, text.lines().count() as u32),
+    )
+}
+
+/// Side-by-side view of two text grids with differing cells marked by `^` in a third
+/// column, `None` when the grids (after [`inline_grid`]) are equal.
+#[must_use]
+pub fn grid_diff(expected: &str, actual: &str) -> Option<String> {
+    let expected = inline_grid(expected);
+    let actual = inline_grid(actual);
+    if expected == actual {
+        return None;
+    }
+    let expected: Vec<Vec<char>> = expected.lines().map(|l| l.chars().collect()).collect();
+    let actual: Vec<Vec<char>> = actual.lines().map(|l| l.chars().collect()).collect();
+    let width = expected
+        .iter()
+        .chain(&actual)
+        .map(Vec::len)
+        .max()
+        .unwrap_or(0)
+        .max("expected".len());
+    let cell = |rows: &[Vec<char>], x: usize, y: usize| {
+        rows.get(y).and_then(|r| r.get(x)).copied().unwrap_or(' ')
+    };
+
+    let mut out = String::new();
+    let _ = writeln!(out, "{:width$} | {:width$} | diff", "expected", "actual");
+    let mut differing = 0;
+    for y in 0..expected.len().max(actual.len()) {
+        let (mut e, mut a, mut d) = (String::new(), String::new(), String::new());
+        for x in 0..width {
+            let (ec, ac) = (cell(&expected, x, y), cell(&actual, x, y));
+            e.push(ec);
+            a.push(ac);
+            if ec == ac {
+                d.push(' ');
+            } else {
+                d.push('^');
+                differing += 1;
+            }
+        }
+        let marker = if d.trim().is_empty() { ' ' } else { '>' };
+        let line = format!("{e} | {a} | {}{marker}", d.trim_end());
+        let _ = writeln!(out, "{}", line.trim_end());
+    }
+    let _ = write!(out, "{differing} differing cells");
+    Some(out)
+}
+
+/// Asserts that two text grids are equal, panicking with a [`grid_diff`] otherwise.
+///
+/// Both sides are formatted with `Display` and normalized with [`inline_grid`], so a
+/// [`GridRenderer`](crate::GridRenderer) can be compared directly to an inline string.
+#[macro_export]
+macro_rules! assert_grid_eq {
+    ($actual:expr, $expected:expr $(,)?) => {
+        if let Some(diff) = $crate::testing::grid_diff(
+            &::std::format!("{}", $expected),
+            &::std::format!("{}", $actual),
+        ) {
+            ::std::panic!("grids differ\n{diff}");
+        }
+    };
+    ($actual:expr, $expected:expr, $($arg:tt)+) => {
+        if let Some(diff) = $crate::testing::grid_diff(
+            &::std::format!("{}", $expected),
+            &::std::format!("{}", $actual),
+        ) {
+            ::std::panic!("grids differ: {}\n{diff}", ::std::format_args!($($arg)+));
+        }
+    };
+}
+pub use crate::assert_grid_eq;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_test::traced_test;
+
+    #[test]
+    #[traced_test]
+    fn inline() {
+        let grid = "
+            #..#
+              ..
+
+            #..#
+        ";
+        assert_eq!(inline_grid(grid), "#..#\n  ..\n\n#..#");
+    }
+
+    #[test]
+    #[traced_test]
+    fn diff() {
+        assert_eq!(grid_diff("ab\ncd", "\n  ab\n  cd\n"), None);
+        let diff = grid_diff("ab\ncd", "ab\nce\nf").unwrap();
+        assert_eq!(
+            diff,
+            "expected | actual   | diff
+ab       | ab       |
+cd       | ce       |  ^>
+         | f        | ^>
+2 differing cells"
+        );
+    }
+
+    #[test]
+    #[traced_test]
+    fn assert_equal() {
+        assert_grid_eq!(
+            String::from("#.\n.#"),
+            "
+            #.
+            .#
+        "
+        );
+    }
+
+    #[test]
+    #[traced_test]
+    fn parse() {
+        let expected = "
+            #.S#
+            ..E#
+        ";
+        let grid = parse_grid(expected);
+        assert_eq!(grid.size(), UVec2::new(4, 2));
+        assert_eq!(grid.walkable_tiles().get(&UVec2::new(2, 1)), Some(&'E'));
+        assert_grid_eq!(
+            grid.renderer()
+                .format_walkable(|_, c| Some(*c))
+                .walkable_char('?'),
+            expected
+        );
+
+        let dots = parse_grid_with("a.\n.b", |c| (c != '.').then_some(c));
+        assert_grid_eq!(dots.renderer().format_walkable(|_, c| Some(*c)), "a#\n#b");
+    }
+
+    #[test]
+    #[traced_test]
+    #[should_panic(expected = "grids differ: after 3 moves")]
+    fn assert_different() {
+        assert_grid_eq!("#.", "##", "after {} moves", 3);
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, FieldsNamed, Ident, LitStr, Path};
+
+/// Derives `parse::derive::AocParse` and `FromStr` for a struct with named fields.
+///
+/// The struct attribute `#[aoc(fmt = "p={x},{y} v={vx},{vy}")]` lays out the input, every
+/// field appears exactly once as `{field}` and `{{`/`}}` escape braces. Fields parse with
+/// their own `AocParse` impl unless they name a nom parser with `#[aoc(with = path)]`, e.g.
+/// `#[aoc(with = parse::vec::parse_ivec2)]`.
+#[proc_macro_derive(AocParse, attributes(aoc))]
+pub fn derive_aoc_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+enum Segment {
+    Literal(String),
+    Field(Ident),
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "AocParse can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &data.fields,
+            "AocParse needs named fields",
+        ));
+    };
+
+    let fmt = fmt_attribute(input)?;
+    let parsers = field_parsers(fields)?;
+
+    let segments = parse_fmt(&fmt)?;
+    let mut used = Vec::new();
+    let mut steps = Vec::new();
+    for segment in &segments {
+        match segment {
+            Segment::Literal(literal) => steps.push(quote! {
+                let __aoc_rest = ::parse::derive::literal(__aoc_rest, #literal)?;
+            }),
+            Segment::Field(ident) => {
+                let Some(field) = fields
+                    .named
+                    .iter()
+                    .find(|f| f.ident.as_ref() == Some(ident))
+                else {
+                    return Err(syn::Error::new(
+                        fmt.span(),
+                        format!("no field named `{ident}`"),
+                    ));
+                };
+                if used.contains(ident) {
+                    return Err(syn::Error::new(
+                        fmt.span(),
+                        format!("field `{ident}` appears twice"),
+                    ));
+                }
+                used.push(ident.clone());
+                let name = ident.to_string();
+                let ty = &field.ty;
+                steps.push(if let Some(parser) = parsers.get(&field.ident) {
+                    quote! {
+                        let (__aoc_rest, #ident): (&str, #ty) =
+                            ::parse::derive::field_with(__aoc_rest, #name, #parser)?;
+                    }
+                } else {
+                    quote! {
+                        let (__aoc_rest, #ident) =
+                            ::parse::derive::field::<#ty>(__aoc_rest, #name)?;
+                    }
+                });
+            }
+        }
+    }
+    if let Some(missing) = fields
+        .named
+        .iter()
+        .filter_map(|f| f.ident.as_ref())
+        .find(|ident| !used.contains(ident))
+    {
+        return Err(syn::Error::new_spanned(
+            missing,
+            format!("field `{missing}` is missing from the fmt"),
+        ));
+    }
+
+    let name = &input.ident;
+    let target = name.to_string();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::parse::derive::AocParse for #name #ty_generics #where_clause {
+            fn parse_prefix(
+                input: &str,
+            ) -> ::core::result::Result<(&str, Self), ::parse::derive::Expected<'_>> {
+                let __aoc_rest = input;
+                #(#steps)*
+                ::core::result::Result::Ok((__aoc_rest, Self { #(#used),* }))
+            }
+        }
+
+        impl #impl_generics ::core::str::FromStr for #name #ty_generics #where_clause {
+            type Err = ::parse::derive::AocParseError;
+
+            fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
+                ::parse::derive::parse_all(s, #target)
+            }
+        }
+    })
+}
+
+fn fmt_attribute(input: &DeriveInput) -> syn::Result<LitStr> {
+    let mut fmt = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("aoc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("fmt") {
+                fmt = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `fmt = \"...\"`"))
+            }
+        })?;
+    }
+    fmt.ok_or_else(|| syn::Error::new(Span::call_site(), "missing #[aoc(fmt = \"...\")] attribute"))
+}
+
+/// Parsers given with `#[aoc(with = path)]` by field.
+fn field_parsers(fields: &FieldsNamed) -> syn::Result<HashMap<Option<Ident>, Path>> {
+    let mut parsers = HashMap::new();
+    for field in &fields.named {
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("aoc")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("with") {
+                    parsers.insert(field.ident.clone(), meta.value()?.parse::<Path>()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `with = path::to::parser`"))
+                }
+            })?;
+        }
+    }
+    Ok(parsers)
+}
+
+fn parse_fmt(fmt: &LitStr) -> syn::Result<Vec<Segment>> {
+    let error = |message: &str| syn::Error::new(fmt.span(), message);
+    let value = fmt.value();
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' if chars.peek() == Some(&c) => {
+                chars.next();
+                literal.push(c);
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => return Err(error("unclosed `{` in fmt")),
+                    }
+                }
+                let ident = syn::parse_str::<Ident>(name.trim())
+                    .map_err(|_| error(&format!("invalid field name `{name}` in fmt")))?;
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(Segment::Field(ident));
+            }
+            '}' => return Err(error("unmatched `}` in fmt, use `}}` for a literal brace")),
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    Ok(segments)
+}
@@ -0,0 +1,41 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use parse::{fast, range::parse_range_tuple};
+use std::hint::black_box;
+
+fn ranges() -> String {
+    (0..1000u64)
+        .map(|i| {
+            let from = i * 7_919_113 + 1_000_000_000;
+            format!("{from}-{}", from + i * 31)
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn range_benchmark(c: &mut Criterion) {
+    let input = ranges();
+    let mut group = c.benchmark_group("parse-ranges");
+    group.bench_function("parse_range_tuple", |b| {
+        b.iter(|| {
+            black_box(&input)
+                .split(',')
+                .map(|text| parse_range_tuple::<u64>(text).expect("valid range"))
+                .fold(0, |sum, (from, to)| sum ^ from ^ to)
+        });
+    });
+    group.bench_function("fast::pair", |b| {
+        b.iter(|| {
+            black_box(&input)
+                .split(',')
+                .map(|text| fast::pair::<u64>(text.as_bytes()).expect("valid range"))
+                .fold(0, |sum, (from, to)| sum ^ from ^ to)
+        });
+    });
+    group.bench_function("fast::integers", |b| {
+        b.iter(|| fast::integers::<u64>(black_box(&input).as_bytes()).fold(0, |sum, n| sum ^ n));
+    });
+    group.finish();
+}
+
+criterion_group!(benches, range_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,195 @@
+//! Column-aligned ASCII text, e.g. numbers written vertically or tables with ragged cells.
+
+use std::ops::Range;
+
+/// Direction to read a column in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadOrder {
+    TopToBottom,
+    BottomToTop,
+}
+
+/// Lines of ASCII text padded with spaces to the widest line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextBlock<'a> {
+    lines: Vec<&'a str>,
+    width: usize,
+}
+impl<'a> TextBlock<'a> {
+    #[must_use]
+    pub fn new(text: &'a str) -> Self {
+        Self::from_lines(text.lines().collect())
+    }
+
+    fn from_lines(lines: Vec<&'a str>) -> Self {
+        let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+        Self { lines, width }
+    }
+
+    #[must_use]
+    pub fn lines(&self) -> &[&'a str] {
+        &self.lines
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Byte at the position, a space past the end of a line.
+    #[must_use]
+    pub fn byte(&self, x: usize, y: usize) -> u8 {
+        self.lines[y].as_bytes().get(x).copied().unwrap_or(b' ')
+    }
+
+    /// Block of the given lines only, e.g. without a trailing operator line.
+    #[must_use]
+    pub fn sub_block(&self, rows: Range<usize>) -> Self {
+        Self::from_lines(self.lines[rows].to_vec())
+    }
+
+    /// Whether column `x` is whitespace on every line.
+    #[must_use]
+    pub fn is_gutter(&self, x: usize) -> bool {
+        (0..self.height()).all(|y| self.byte(x, y).is_ascii_whitespace())
+    }
+
+    /// Maximal runs of columns between gutters.
+    #[must_use]
+    pub fn gutter_columns(&self) -> Vec<Range<usize>> {
+        let mut columns = Vec::new();
+        let mut start = None;
+        for x in 0..=self.width {
+            match (start, x == self.width || self.is_gutter(x)) {
+                (None, false) => start = Some(x),
+                (Some(from), true) => {
+                    columns.push(from..x);
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        columns
+    }
+
+    /// Columns starting at every marker of line `y` and ending at the next one, trailing
+    /// gutters excluded.
+    #[must_use]
+    pub fn marker_columns(&self, y: usize, is_marker: impl Fn(u8) -> bool) -> Vec<Range<usize>> {
+        let starts: Vec<_> = (0..self.width)
+            .filter(|x| is_marker(self.byte(*x, y)))
+            .collect();
+        starts
+            .iter()
+            .enumerate()
+            .map(|(i, start)| {
+                let mut end = starts.get(i + 1).copied().unwrap_or(self.width);
+                while end > *start + 1 && self.is_gutter(end - 1) {
+                    end -= 1;
+                }
+                *start..end
+            })
+            .collect()
+    }
+
+    /// Part of every line within the columns, shorter lines give shorter or empty cells.
+    pub fn cells(&self, columns: Range<usize>) -> impl Iterator<Item = &'a str> + '_ {
+        self.lines.iter().map(move |line| {
+            let end = columns.end.min(line.len());
+            line.get(columns.start.min(end)..end).unwrap_or_default()
+        })
+    }
+
+    /// Column `x` read in `order`, padding included.
+    #[must_use]
+    pub fn column(&self, x: usize, order: ReadOrder) -> String {
+        let ys: Box<dyn Iterator<Item = usize>> = match order {
+            ReadOrder::TopToBottom => Box::new(0..self.height()),
+            ReadOrder::BottomToTop => Box::new((0..self.height()).rev()),
+        };
+        ys.map(|y| char::from(self.byte(x, y))).collect()
+    }
+
+    /// Lines become columns and columns become lines.
+    #[must_use]
+    pub fn transpose(&self) -> Vec<String> {
+        (0..self.width)
+            .map(|x| self.column(x, ReadOrder::TopToBottom))
+            .collect()
+    }
+
+    /// Digits of each column in the range read in `order`, columns without digits give an
+    /// empty string.
+    pub fn vertical_digits(
+        &self,
+        columns: Range<usize>,
+        order: ReadOrder,
+    ) -> impl Iterator<Item = String> + '_ {
+        columns.map(move |x| {
+            self.column(x, order)
+                .chars()
+                .filter(char::is_ascii_digit)
+                .collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    const WORKSHEET: &str = "\
+123 328  51 64
+ 45 64  387 23
+  6 98  215 314
+*   +   *   +  ";
+
+    #[test]
+    #[traced_test]
+    fn gutters() {
+        let block = TextBlock::new(WORKSHEET);
+        assert_eq!(block.gutter_columns(), [0..3, 4..7, 8..11, 12..15]);
+        assert_eq!(
+            block.cells(8..11).collect::<Vec<_>>(),
+            [" 51", "387", "215", "*  "]
+        );
+    }
+
+    #[test]
+    #[traced_test]
+    fn markers() {
+        let block = TextBlock::new(WORKSHEET);
+        let ops = block.height() - 1;
+        let columns = block.marker_columns(ops, |b| b == b'+' || b == b'*');
+        assert_eq!(columns, [0..3, 4..7, 8..11, 12..15]);
+
+        let numbers = block.sub_block(0..ops);
+        assert_eq!(
+            numbers
+                .vertical_digits(columns[3].clone(), ReadOrder::TopToBottom)
+                .collect::<Vec<_>>(),
+            ["623", "431", "4"]
+        );
+        assert_eq!(
+            numbers
+                .vertical_digits(columns[0].clone(), ReadOrder::BottomToTop)
+                .collect::<Vec<_>>(),
+            ["1", "42", "653"]
+        );
+    }
+
+    #[test_case("ab\ncd" => vec!["ac", "bd"])]
+    #[test_case("abc\nd" => vec!["ad", "b ", "c "])]
+    #[test_case("" => Vec::<String>::new())]
+    #[traced_test]
+    fn transpose(text: &str) -> Vec<String> {
+        TextBlock::new(text).transpose()
+    }
+}
@@ -0,0 +1,194 @@
+use std::str::FromStr;
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{digit1, one_of, space0, space1},
+    combinator::{map_res, opt, recognize},
+    error::{Error, ErrorKind},
+    multi::separated_list1,
+    sequence::{delimited, pair, preceded, tuple},
+    IResult,
+};
+
+/// Integer with an optional `+` or `-` sign, `T` decides whether negative numbers are valid.
+#[allow(clippy::missing_errors_doc)]
+pub fn number<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse)(input)
+}
+
+#[allow(clippy::missing_errors_doc)]
+pub fn number_res<T: FromStr>(input: &str) -> anyhow::Result<T> {
+    let (_, number) = number(input).map_err(nom::Err::<Error<&str>>::to_owned)?;
+    Ok(number)
+}
+
+/// `sep` with optional whitespace around it, a blank `sep` matches any run of whitespace.
+pub fn separator(sep: &str) -> impl FnMut(&str) -> IResult<&str, &str> + '_ {
+    move |input| {
+        if sep.trim().is_empty() {
+            space1(input)
+        } else {
+            delimited(space0, tag(sep.trim()), space0)(input)
+        }
+    }
+}
+
+/// Exactly `N` numbers separated by `sep`, e.g. `vector::<i32, 3>(",")` for `1,-2,3`.
+pub fn vector<T: FromStr, const N: usize>(
+    sep: &str,
+) -> impl FnMut(&str) -> IResult<&str, [T; N]> + '_ {
+    move |mut input| {
+        let mut values = Vec::with_capacity(N);
+        for i in 0..N {
+            if i > 0 {
+                (input, _) = separator(sep)(input)?;
+            }
+            let value;
+            (input, value) = number(input)?;
+            values.push(value);
+        }
+        let Ok(values) = values.try_into() else {
+            unreachable!("parsed exactly {N} values");
+        };
+        Ok((input, values))
+    }
+}
+
+#[allow(clippy::missing_errors_doc)]
+pub fn vector_res<T: FromStr, const N: usize>(input: &str, sep: &str) -> anyhow::Result<[T; N]> {
+    let (_, vector) = vector(sep)(input).map_err(nom::Err::<Error<&str>>::to_owned)?;
+    Ok(vector)
+}
+
+/// One or more numbers separated by `sep`.
+pub fn number_list<T: FromStr>(sep: &str) -> impl FnMut(&str) -> IResult<&str, Vec<T>> + '_ {
+    move |input| separated_list1(separator(sep), number)(input)
+}
+
+#[allow(clippy::missing_errors_doc)]
+pub fn number_list_res<T: FromStr>(input: &str, sep: &str) -> anyhow::Result<Vec<T>> {
+    let (_, numbers) = number_list(sep)(input).map_err(nom::Err::<Error<&str>>::to_owned)?;
+    Ok(numbers)
+}
+
+/// Value labelled by `key` as in `key=value`, `Key: value` or `X+94`.
+pub fn field<'a, O>(
+    key: &'a str,
+    value: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O> {
+    preceded(tuple((tag(key), space0, opt(one_of("=:")), space0)), value)
+}
+
+#[allow(clippy::missing_errors_doc)]
+pub fn field_res<'a, O>(
+    input: &'a str,
+    key: &'a str,
+    value: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> anyhow::Result<O> {
+    let (_, value) = field(key, value)(input).map_err(nom::Err::<Error<&str>>::to_owned)?;
+    Ok(value)
+}
+
+/// Every integer in the text, skipping anything else.
+///
+/// A `-` is a sign unless it follows a letter or digit, so `3-5` is `[3, 5]` but `x=-3` is `[-3]`.
+#[allow(clippy::missing_errors_doc)]
+pub fn all_numbers<T: FromStr>(input: &str) -> IResult<&str, Vec<T>> {
+    let bytes = input.as_bytes();
+    let is_digit = |i: usize| bytes.get(i).is_some_and(u8::is_ascii_digit);
+    let mut numbers = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let sign =
+            bytes[i] == b'-' && is_digit(i + 1) && !(i > 0 && bytes[i - 1].is_ascii_alphanumeric());
+        if !sign && !is_digit(i) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        i += 1;
+        while is_digit(i) {
+            i += 1;
+        }
+        let number = input[start..i]
+            .parse()
+            .map_err(|_| nom::Err::Error(Error::new(&input[start..], ErrorKind::Digit)))?;
+        numbers.push(number);
+    }
+    Ok((&input[i..], numbers))
+}
+
+#[allow(clippy::missing_errors_doc)]
+pub fn all_numbers_res<T: FromStr>(input: &str) -> anyhow::Result<Vec<T>> {
+    let (_, numbers) = all_numbers(input).map_err(nom::Err::<Error<&str>>::to_owned)?;
+    Ok(numbers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::sequence::separated_pair;
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    #[test_case("+94" => Some(94))]
+    #[test_case("-3" => Some(-3))]
+    #[test_case("12abc" => Some(12))]
+    #[test_case("x" => None)]
+    #[traced_test]
+    fn signed(input: &str) -> Option<i32> {
+        number_res(input).ok()
+    }
+
+    #[test]
+    #[traced_test]
+    fn unsigned() {
+        assert_eq!(number_res::<u32>("+7").unwrap(), 7);
+        assert!(number_res::<u32>("-7").is_err());
+        assert!(number_res::<u8>("300").is_err());
+    }
+
+    #[test_case("162,817,812", "," => [162, 817, 812])]
+    #[test_case("1 , -2,3", "," => [1, -2, 3])]
+    #[test_case("4  5\t6", " " => [4, 5, 6])]
+    #[traced_test]
+    fn vector3(input: &str, sep: &str) -> [i64; 3] {
+        vector_res(input, sep).unwrap()
+    }
+
+    #[test]
+    #[traced_test]
+    fn lists() {
+        assert_eq!(
+            number_list_res::<u32>("7 6 4 2 1", " ").unwrap(),
+            [7, 6, 4, 2, 1]
+        );
+        assert_eq!(number_list(",")("3,4,x"), Ok((",x", vec![3u8, 4])));
+        assert!(vector_res::<u32, 3>("1,2", ",").is_err());
+    }
+
+    #[test]
+    #[traced_test]
+    fn fields() {
+        let mut robot = tuple((
+            field("p", vector::<i32, 2>(",")),
+            preceded(space1, field("v", vector::<i32, 2>(","))),
+        ));
+        assert_eq!(robot("p=0,4 v=3,-3"), Ok(("", ([0, 4], [3, -3]))));
+
+        let button = separated_pair(field("X", number), separator(","), field("Y", number));
+        assert_eq!(
+            field_res("Button A: X+94, Y+34", "Button A", button).unwrap(),
+            (94u64, 34u64)
+        );
+    }
+
+    #[test_case("p=0,4 v=3,-3" => vec![0, 4, 3, -3])]
+    #[test_case("3-5 x=-3 day15" => vec![3, 5, -3, 15])]
+    #[test_case("Prize: X=8400, Y=5400" => vec![8400, 5400])]
+    #[test_case("no numbers -" => Vec::<i64>::new())]
+    #[traced_test]
+    fn extract(line: &str) -> Vec<i64> {
+        all_numbers_res(line).unwrap()
+    }
+}
@@ -0,0 +1,303 @@
+use derive_more::derive::{Display, Error};
+use glam::{I64Vec2, I64Vec3, IVec2, IVec3, U64Vec2, U64Vec3, UVec2, UVec3};
+use nom::{
+    error::{Error as NomError, ErrorKind},
+    IResult,
+};
+pub use parse_derive::AocParse;
+
+use crate::{
+    combinators::{number, separator},
+    vec::{
+        parse_i64vec2, parse_i64vec3, parse_ivec2, parse_ivec3, parse_u64vec2, parse_u64vec3,
+        parse_uvec2, parse_uvec3,
+    },
+};
+
+/// Where and why a parser stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expected<'a> {
+    /// Input left at the failing position.
+    pub rest: &'a str,
+    pub what: String,
+}
+
+#[derive(Debug, Error, Display, PartialEq, Eq)]
+#[display("Cannot parse {target} from {input:?}: expected {expected} at column {column}")]
+pub struct AocParseError {
+    pub target: &'static str,
+    pub input: String,
+    pub column: usize,
+    pub expected: String,
+}
+
+/// Value parsed from the start of a line, usually through `#[derive(AocParse)]`.
+pub trait AocParse: Sized {
+    /// # Errors
+    ///
+    /// Returns the position where parsing failed and what was expected there.
+    fn parse_prefix(input: &str) -> Result<(&str, Self), Expected<'_>>;
+
+    /// [`AocParse::parse_prefix`] for use within nom combinators.
+    ///
+    /// # Errors
+    ///
+    /// Returns a nom error at the position where parsing failed.
+    fn parse_nom(input: &str) -> IResult<&str, Self> {
+        Self::parse_prefix(input)
+            .map_err(|e| nom::Err::Error(NomError::new(e.rest, ErrorKind::Verify)))
+    }
+}
+
+/// Parses the whole input apart from surrounding whitespace.
+///
+/// # Errors
+///
+/// Returns an error naming the column and what was expected there.
+pub fn parse_all<T: AocParse>(input: &str, target: &'static str) -> Result<T, AocParseError> {
+    let input = input.trim();
+    let error = |e: Expected| AocParseError {
+        target,
+        input: input.to_string(),
+        column: input.len() - e.rest.len() + 1,
+        expected: e.what,
+    };
+    let (rest, value) = T::parse_prefix(input).map_err(error)?;
+    if !rest.is_empty() {
+        return Err(error(Expected {
+            rest,
+            what: "end of input".to_string(),
+        }));
+    }
+    Ok(value)
+}
+
+#[doc(hidden)]
+pub fn literal<'a>(input: &'a str, literal: &str) -> Result<&'a str, Expected<'a>> {
+    input.strip_prefix(literal).ok_or_else(|| Expected {
+        rest: input,
+        what: format!("{literal:?}"),
+    })
+}
+
+#[doc(hidden)]
+pub fn field<'a, T: AocParse>(input: &'a str, name: &str) -> Result<(&'a str, T), Expected<'a>> {
+    T::parse_prefix(input).map_err(|e| Expected {
+        what: format!("{name} ({})", e.what),
+        ..e
+    })
+}
+
+#[doc(hidden)]
+pub fn field_with<'a, T>(
+    input: &'a str,
+    name: &str,
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, T>,
+) -> Result<(&'a str, T), Expected<'a>> {
+    parser(input).map_err(|e| Expected {
+        rest: match e {
+            nom::Err::Error(e) | nom::Err::Failure(e) => e.input,
+            nom::Err::Incomplete(_) => input,
+        },
+        what: name.to_string(),
+    })
+}
+
+fn from_nom<'a, T>(
+    input: &'a str,
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, T>,
+    what: &str,
+) -> Result<(&'a str, T), Expected<'a>> {
+    parser(input).map_err(|_| Expected {
+        rest: input,
+        what: what.to_string(),
+    })
+}
+
+macro_rules! impl_aoc_parse_number {
+    ($($int: ty),+) => {
+        $(
+            impl AocParse for $int {
+                fn parse_prefix(input: &str) -> Result<(&str, Self), Expected<'_>> {
+                    from_nom(input, number, concat!(stringify!($int), " number"))
+                }
+            }
+        )+
+    };
+}
+
+impl_aoc_parse_number!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_aoc_parse_vec {
+    ($($vec: ty => $parser: ident, $what: literal);+ $(;)?) => {
+        $(
+            impl AocParse for $vec {
+                fn parse_prefix(input: &str) -> Result<(&str, Self), Expected<'_>> {
+                    from_nom(input, $parser, $what)
+                }
+            }
+        )+
+    };
+}
+
+impl_aoc_parse_vec!(
+    IVec2 => parse_ivec2, "x,y";
+    UVec2 => parse_uvec2, "x,y";
+    I64Vec2 => parse_i64vec2, "x,y";
+    U64Vec2 => parse_u64vec2, "x,y";
+    IVec3 => parse_ivec3, "x,y,z";
+    UVec3 => parse_uvec3, "x,y,z";
+    I64Vec3 => parse_i64vec3, "x,y,z";
+    U64Vec3 => parse_u64vec3, "x,y,z";
+);
+
+impl AocParse for char {
+    fn parse_prefix(input: &str) -> Result<(&str, Self), Expected<'_>> {
+        let mut chars = input.chars();
+        let c = chars.next().ok_or_else(|| Expected {
+            rest: input,
+            what: "a char".to_string(),
+        })?;
+        Ok((chars.as_str(), c))
+    }
+}
+
+/// A word of letters, digits, `_` and `-`.
+impl AocParse for String {
+    fn parse_prefix(input: &str) -> Result<(&str, Self), Expected<'_>> {
+        let len = input
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(input.len());
+        if len == 0 {
+            return Err(Expected {
+                rest: input,
+                what: "a word".to_string(),
+            });
+        }
+        Ok((&input[len..], input[..len].to_string()))
+    }
+}
+
+/// One or more values separated by commas.
+impl<T: AocParse> AocParse for Vec<T> {
+    fn parse_prefix(input: &str) -> Result<(&str, Self), Expected<'_>> {
+        let (mut rest, first) = T::parse_prefix(input)?;
+        let mut values = vec![first];
+        while let Ok((after_separator, _)) = separator(",")(rest) {
+            let Ok((after, value)) = T::parse_prefix(after_separator) else {
+                break;
+            };
+            values.push(value);
+            rest = after;
+        }
+        Ok((rest, values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    #[derive(Debug, PartialEq, AocParse)]
+    #[aoc(fmt = "p={x},{y} v={vx},{vy}")]
+    struct Robot {
+        x: i32,
+        y: i32,
+        vx: i32,
+        vy: i32,
+    }
+
+    #[derive(Debug, PartialEq, AocParse)]
+    #[aoc(fmt = "p={pos} v={vel}")]
+    struct VecRobot {
+        #[aoc(with = crate::vec::parse_ivec2)]
+        pos: IVec2,
+        vel: IVec2,
+    }
+
+    #[derive(Debug, PartialEq, AocParse)]
+    #[aoc(fmt = "Button A: X+{a}, Y+{ay}\nButton B: X+{b}, Y+{by}\nPrize: X={prize}")]
+    struct Machine {
+        a: u64,
+        ay: u64,
+        b: u64,
+        by: u64,
+        prize: Vec<Prize>,
+    }
+
+    #[derive(Debug, PartialEq, AocParse)]
+    #[aoc(fmt = "{value}{{{unit}}}")]
+    struct Prize {
+        value: u64,
+        unit: String,
+    }
+
+    #[test]
+    #[traced_test]
+    fn robot() {
+        let robot: Robot = "p=0,4 v=3,-3".parse().unwrap();
+        assert_eq!(
+            robot,
+            Robot {
+                x: 0,
+                y: 4,
+                vx: 3,
+                vy: -3
+            }
+        );
+        let robot: VecRobot = "p=0,4 v=3,-3\n".parse().unwrap();
+        assert_eq!(robot.pos, IVec2::new(0, 4));
+        assert_eq!(robot.vel, IVec2::new(3, -3));
+    }
+
+    #[test]
+    #[traced_test]
+    fn nested() {
+        let machine: Machine =
+            "Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400{x}, 5400{y}"
+                .parse()
+                .unwrap();
+        assert_eq!((machine.a, machine.by), (94, 67));
+        assert_eq!(
+            machine.prize,
+            [
+                Prize {
+                    value: 8400,
+                    unit: "x".to_string()
+                },
+                Prize {
+                    value: 5400,
+                    unit: "y".to_string()
+                }
+            ]
+        );
+    }
+
+    #[test_case("p=0,4 v=3,x" => "Cannot parse Robot from \"p=0,4 v=3,x\": expected vy (i32 number) at column 11")]
+    #[test_case("p=0,4 w=3,3" => "Cannot parse Robot from \"p=0,4 w=3,3\": expected \" v=\" at column 6")]
+    #[test_case("p=0,4 v=3,3 !" => "Cannot parse Robot from \"p=0,4 v=3,3 !\": expected end of input at column 12")]
+    #[traced_test]
+    fn errors(input: &str) -> String {
+        input.parse::<Robot>().unwrap_err().to_string()
+    }
+
+    #[test]
+    #[traced_test]
+    fn with_error() {
+        let err = "p=0;4 v=3,3".parse::<VecRobot>().unwrap_err();
+        assert_eq!((err.column, err.expected.as_str()), (4, "pos"));
+    }
+
+    #[test]
+    #[traced_test]
+    fn nom() {
+        let (rest, robots) = nom::multi::separated_list1(
+            nom::character::complete::newline,
+            Robot::parse_nom,
+        )("p=0,4 v=3,-3\np=6,3 v=-1,-3")
+        .unwrap();
+        assert_eq!((rest, robots.len()), ("", 2));
+    }
+}
@@ -0,0 +1,248 @@
+//! Allocation free integer scanning for hot loops.
+//!
+//! Nothing is validated beyond "is a digit", values that do not fit wrap around.
+//!
+//! With the `swar` feature the bytes are scanned eight at a time, both to find the digit
+//! runs and to convert them.
+
+use std::marker::PhantomData;
+
+/// Integer built from a sign and up to 64 bits of magnitude.
+pub trait FastInt: Copy {
+    const SIGNED: bool;
+
+    fn from_magnitude(negative: bool, magnitude: u64) -> Self;
+}
+
+macro_rules! impl_fast_int {
+    ($signed: literal => $($int: ty),+) => {
+        $(
+            impl FastInt for $int {
+                const SIGNED: bool = $signed;
+
+                #[inline]
+                fn from_magnitude(negative: bool, magnitude: u64) -> Self {
+                    if negative {
+                        (magnitude as $int).wrapping_neg()
+                    } else {
+                        magnitude as $int
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_fast_int!(false => u8, u16, u32, u64, usize);
+impl_fast_int!(true => i8, i16, i32, i64, isize);
+
+/// Every integer in the bytes, skipping anything else.
+///
+/// For signed types a `-` is a sign unless it follows a letter or digit, so `3-5` is
+/// `3, 5` but `x=-3` is `-3`.
+#[must_use]
+pub fn integers<T: FastInt>(bytes: &[u8]) -> Integers<'_, T> {
+    Integers {
+        bytes,
+        pos: 0,
+        int: PhantomData,
+    }
+}
+
+/// Iterator of [`integers`].
+#[derive(Debug, Clone)]
+pub struct Integers<'a, T> {
+    bytes: &'a [u8],
+    pos: usize,
+    int: PhantomData<T>,
+}
+impl<T: FastInt> Iterator for Integers<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let bytes = self.bytes;
+        let start = self.pos + first_digit(&bytes[self.pos..])?;
+        let negative = T::SIGNED
+            && start > 0
+            && bytes[start - 1] == b'-'
+            && !(start > 1 && bytes[start - 2].is_ascii_alphanumeric());
+        let len = digit_run(&bytes[start..]);
+        self.pos = start + len;
+        Some(T::from_magnitude(
+            negative,
+            digits(&bytes[start..start + len]),
+        ))
+    }
+}
+
+/// First two integers, e.g. the bounds of `11-22`.
+#[must_use]
+pub fn pair<T: FastInt>(bytes: &[u8]) -> Option<(T, T)> {
+    let mut integers = integers(bytes);
+    Some((integers.next()?, integers.next()?))
+}
+
+/// Position of the first ASCII digit.
+#[inline]
+fn first_digit(bytes: &[u8]) -> Option<usize> {
+    #[cfg(feature = "swar")]
+    {
+        let mut chunks = bytes.chunks_exact(8);
+        for (i, chunk) in chunks.by_ref().enumerate() {
+            let mask = digit_mask(chunk.try_into().unwrap_or_default());
+            if mask != 0 {
+                return Some(i * 8 + mask.trailing_zeros() as usize / 8);
+            }
+        }
+        let offset = bytes.len() - chunks.remainder().len();
+        chunks
+            .remainder()
+            .iter()
+            .position(u8::is_ascii_digit)
+            .map(|i| offset + i)
+    }
+    #[cfg(not(feature = "swar"))]
+    bytes.iter().position(u8::is_ascii_digit)
+}
+
+/// Number of ASCII digits the bytes start with.
+#[inline]
+fn digit_run(bytes: &[u8]) -> usize {
+    #[cfg(feature = "swar")]
+    {
+        let mut chunks = bytes.chunks_exact(8);
+        for (i, chunk) in chunks.by_ref().enumerate() {
+            let others = !digit_mask(chunk.try_into().unwrap_or_default()) & 0x8080_8080_8080_8080;
+            if others != 0 {
+                return i * 8 + others.trailing_zeros() as usize / 8;
+            }
+        }
+        let offset = bytes.len() - chunks.remainder().len();
+        offset
+            + chunks
+                .remainder()
+                .iter()
+                .position(|b| !b.is_ascii_digit())
+                .unwrap_or(chunks.remainder().len())
+    }
+    #[cfg(not(feature = "swar"))]
+    bytes
+        .iter()
+        .position(|b| !b.is_ascii_digit())
+        .unwrap_or(bytes.len())
+}
+
+/// High bit of every byte that is an ASCII digit, the first byte is the lowest.
+#[cfg(feature = "swar")]
+#[inline]
+fn digit_mask(chunk: [u8; 8]) -> u64 {
+    const HIGH: u64 = 0x8080_8080_8080_8080;
+    let chunk = u64::from_le_bytes(chunk);
+    // Without the high bit neither the addition nor the subtraction crosses into the next byte.
+    let low = chunk & !HIGH;
+    let at_least_0 = (low | HIGH) - 0x3030_3030_3030_3030;
+    let above_9 = low + 0x4646_4646_4646_4646;
+    at_least_0 & !above_9 & !chunk & HIGH
+}
+
+/// Value of a run of ASCII digits, which is not checked.
+#[must_use]
+#[inline]
+pub fn digits(digits: &[u8]) -> u64 {
+    #[cfg(feature = "swar")]
+    {
+        let mut chunks = digits.chunks_exact(8);
+        let value = chunks.by_ref().fold(0u64, |value, chunk| {
+            value
+                .wrapping_mul(100_000_000)
+                .wrapping_add(swar_8_digits(chunk.try_into().unwrap_or_default()))
+        });
+        chunks.remainder().iter().fold(value, |value, d| {
+            value
+                .wrapping_mul(10)
+                .wrapping_add(u64::from(d.wrapping_sub(b'0')))
+        })
+    }
+    #[cfg(not(feature = "swar"))]
+    digits.iter().fold(0u64, |value, d| {
+        value
+            .wrapping_mul(10)
+            .wrapping_add(u64::from(d.wrapping_sub(b'0')))
+    })
+}
+
+/// Exactly `N` digits, `None` if any byte is not a digit.
+#[must_use]
+#[inline]
+pub fn fixed_width<const N: usize>(bytes: &[u8; N]) -> Option<u64> {
+    bytes.iter().all(u8::is_ascii_digit).then(|| digits(bytes))
+}
+
+/// Eight ASCII digits at once, as in "SIMD within a register".
+#[cfg(feature = "swar")]
+#[must_use]
+#[inline]
+pub fn swar_8_digits(chunk: [u8; 8]) -> u64 {
+    // Little endian puts the first digit in the lowest byte, pairs of digits are combined
+    // into bytes, then pairs of bytes into 16 bit and finally 32 bit lanes.
+    let chunk = u64::from_le_bytes(chunk) & 0x0f0f_0f0f_0f0f_0f0f;
+    let chunk = (chunk & 0x000f_000f_000f_000f) * 10 + ((chunk >> 8) & 0x000f_000f_000f_000f);
+    let chunk = (chunk & 0x0000_00ff_0000_00ff) * 100 + ((chunk >> 16) & 0x0000_00ff_0000_00ff);
+    (chunk & 0x0000_0000_0000_ffff) * 10_000 + (chunk >> 32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    #[test_case("11-22,95-115,998-1012" => vec![11, 22, 95, 115, 998, 1012])]
+    #[test_case("p=0,4 v=3,-3" => vec![0, 4, 3, -3])]
+    #[test_case("3-5 x=-3 -7 a-8" => vec![3, 5, -3, -7, 8])]
+    #[test_case(" 123 328  51 64 \n" => vec![123, 328, 51, 64])]
+    #[test_case("-" => Vec::<i64>::new())]
+    #[test_case("x=1234567890123,y=-98765432 é/7\t:0" => vec![1_234_567_890_123, -98_765_432, 7, 0])]
+    #[traced_test]
+    fn signed(text: &str) -> Vec<i64> {
+        integers(text.as_bytes()).collect()
+    }
+
+    #[test]
+    #[traced_test]
+    fn unsigned() {
+        assert_eq!(integers::<u32>(b"x=-3, 7").collect::<Vec<_>>(), [3, 7]);
+        assert_eq!(
+            pair::<u64>(b"1188511880-1188511890"),
+            Some((1_188_511_880, 1_188_511_890))
+        );
+        assert_eq!(pair::<u64>(b"12"), None);
+    }
+
+    #[test_case("0" => 0)]
+    #[test_case("1234567" => 1_234_567)]
+    #[test_case("12345678" => 12_345_678)]
+    #[test_case("1234567890123456789" => 1_234_567_890_123_456_789)]
+    #[traced_test]
+    fn runs(text: &str) -> u64 {
+        assert_eq!(text.parse::<u64>().unwrap(), digits(text.as_bytes()));
+        digits(text.as_bytes())
+    }
+
+    #[test]
+    #[traced_test]
+    fn not_digits() {
+        assert_eq!(digits(b"1 "), 10 + u64::from(b' '.wrapping_sub(b'0')));
+        assert_eq!(digit_run(b"0123456789:"), 10);
+        assert_eq!(digit_run(b"12345678"), 8);
+        assert_eq!(first_digit(b"//:://::/\xff\x80\xb09"), Some(12));
+        assert_eq!(first_digit(b"abcdefghij"), None);
+    }
+
+    #[test]
+    #[traced_test]
+    fn fixed() {
+        assert_eq!(fixed_width(b"0042"), Some(42));
+        assert_eq!(fixed_width(b"4 2"), None);
+    }
+}
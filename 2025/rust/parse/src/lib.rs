@@ -1,2 +1,9 @@
+extern crate self as parse;
+
+pub mod columns;
+pub mod combinators;
+pub mod derive;
+pub mod fast;
 pub mod range;
+pub mod sections;
 pub mod vec;
@@ -5,6 +5,9 @@ use std::{
 
 use anyhow::Context;
 
+/// # Errors
+///
+/// Returns an error unless the text is two numbers separated by `-`.
 pub fn parse_range<T>(text: &str) -> anyhow::Result<Range<T>>
 where
     T: FromStr<Err: std::error::Error + Send + Sync + 'static>,
@@ -13,6 +16,9 @@ where
     Ok(from..to)
 }
 
+/// # Errors
+///
+/// Returns an error unless the text is two numbers separated by `-`.
 pub fn parse_inclusive_range<T>(text: &str) -> anyhow::Result<RangeInclusive<T>>
 where
     T: FromStr<Err: std::error::Error + Send + Sync + 'static>,
@@ -21,6 +27,9 @@ where
     Ok(from..=to)
 }
 
+/// # Errors
+///
+/// Returns an error unless the text is two numbers separated by `-`.
 pub fn parse_range_tuple<T>(text: &str) -> anyhow::Result<(T, T)>
 where
     T: FromStr<Err: std::error::Error + Send + Sync + 'static>,
@@ -0,0 +1,352 @@
+use derive_more::derive::{Display, Error};
+use glam::UVec2;
+use std::{fmt::Display, ops::RangeInclusive, str::FromStr};
+
+use crate::range::parse_inclusive_range;
+
+/// Why an input did not match the declared sections, blocks and lines count from 1.
+#[derive(Debug, Error, Display, PartialEq, Eq)]
+pub enum SectionError {
+    #[display("Block {block} ({kind}) is missing, the input has {found} blocks")]
+    MissingBlock {
+        block: usize,
+        kind: &'static str,
+        found: usize,
+    },
+    #[display("Unexpected block {block} at line {line}, expected {expected} blocks")]
+    UnexpectedBlock {
+        block: usize,
+        line: usize,
+        expected: usize,
+    },
+    #[display("Block {block} ({kind}), line {line}: {reason}")]
+    InvalidLine {
+        block: usize,
+        kind: &'static str,
+        line: usize,
+        reason: String,
+    },
+}
+
+/// Run of non-empty lines separated from the rest of the input by blank lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Block<'a> {
+    pub index: usize,
+    /// Input line of the first line of the block.
+    pub first_line: usize,
+    /// Block lines without the trailing line break.
+    pub text: &'a str,
+}
+impl<'a> Block<'a> {
+    /// Lines together with their input line.
+    pub fn lines(&self) -> impl Iterator<Item = (usize, &'a str)> {
+        let first_line = self.first_line;
+        self.text
+            .lines()
+            .enumerate()
+            .map(move |(i, line)| (first_line + i, line))
+    }
+
+    #[must_use]
+    pub fn error(&self, kind: &'static str, line: usize, reason: impl Display) -> SectionError {
+        SectionError::InvalidLine {
+            block: self.index,
+            kind,
+            line,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// Splits the input at blank lines, surrounding blank lines are ignored.
+#[must_use]
+pub fn blocks(input: &str) -> Vec<Block<'_>> {
+    let mut blocks = Vec::new();
+    let mut start = None;
+    let mut offset = 0;
+    let mut end = 0;
+    for (i, line) in input.split_inclusive('\n').enumerate() {
+        let content = line.trim_end_matches(['\n', '\r']);
+        if content.is_empty() {
+            if let Some((first_line, from)) = start.take() {
+                blocks.push(Block {
+                    index: blocks.len() + 1,
+                    first_line,
+                    text: &input[from..end],
+                });
+            }
+        } else {
+            start.get_or_insert((i + 1, offset));
+            end = offset + content.len();
+        }
+        offset += line.len();
+    }
+    if let Some((first_line, from)) = start {
+        blocks.push(Block {
+            index: blocks.len() + 1,
+            first_line,
+            text: &input[from..end],
+        });
+    }
+    blocks
+}
+
+/// Typed content of a single block.
+pub trait Section<'a>: Sized {
+    /// Name of the section in errors.
+    const KIND: &'static str;
+
+    /// # Errors
+    ///
+    /// Returns [`SectionError::InvalidLine`] for the first line not matching the section.
+    fn from_block(block: &Block<'a>) -> Result<Self, SectionError>;
+}
+
+/// Tuple of [`Section`]s, one per block of the input.
+pub trait Sections<'a>: Sized {
+    const KINDS: &'static [&'static str];
+
+    /// # Errors
+    ///
+    /// Returns an error when a block does not match its section.
+    fn from_blocks(blocks: &[Block<'a>]) -> Result<Self, SectionError>;
+}
+
+/// Splits the input into blocks and parses them as the declared sections, e.g.
+/// `let (map, Lines(moves)): (GridBlock, Lines) = parse_sections(input)?`.
+///
+/// # Errors
+///
+/// Returns an error naming the block (and line) when the number of blocks differs from the
+/// number of sections or a block does not match its section.
+pub fn parse_sections<'a, S: Sections<'a>>(input: &'a str) -> Result<S, SectionError> {
+    let blocks = blocks(input);
+    if let Some(extra) = blocks.get(S::KINDS.len()) {
+        return Err(SectionError::UnexpectedBlock {
+            block: extra.index,
+            line: extra.first_line,
+            expected: S::KINDS.len(),
+        });
+    }
+    if let Some(kind) = S::KINDS.get(blocks.len()) {
+        return Err(SectionError::MissingBlock {
+            block: blocks.len() + 1,
+            kind,
+            found: blocks.len(),
+        });
+    }
+    S::from_blocks(&blocks)
+}
+
+macro_rules! impl_sections {
+    ($($section: ident $index: tt),+) => {
+        impl<'a, $($section: Section<'a>),+> Sections<'a> for ($($section,)+) {
+            const KINDS: &'static [&'static str] = &[$($section::KIND),+];
+
+            fn from_blocks(blocks: &[Block<'a>]) -> Result<Self, SectionError> {
+                Ok(($($section::from_block(&blocks[$index])?,)+))
+            }
+        }
+    };
+}
+
+impl_sections!(A 0);
+impl_sections!(A 0, B 1);
+impl_sections!(A 0, B 1, C 2);
+impl_sections!(A 0, B 1, C 2, D 3);
+
+/// The block text as is.
+impl<'a> Section<'a> for &'a str {
+    const KIND: &'static str = "text";
+
+    fn from_block(block: &Block<'a>) -> Result<Self, SectionError> {
+        Ok(block.text)
+    }
+}
+
+/// Lines of the block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lines<'a>(pub Vec<&'a str>);
+impl<'a> Section<'a> for Lines<'a> {
+    const KIND: &'static str = "lines";
+
+    fn from_block(block: &Block<'a>) -> Result<Self, SectionError> {
+        Ok(Lines(block.text.lines().collect()))
+    }
+}
+
+/// Numbers separated by whitespace, commas or line breaks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Numbers<T>(pub Vec<T>);
+impl<'a, T: FromStr<Err: Display>> Section<'a> for Numbers<T> {
+    const KIND: &'static str = "numbers";
+
+    fn from_block(block: &Block<'a>) -> Result<Self, SectionError> {
+        let mut numbers = Vec::new();
+        for (line, text) in block.lines() {
+            for number in text.split([' ', '\t', ',']).filter(|n| !n.is_empty()) {
+                numbers.push(number.parse().map_err(|e| {
+                    block.error(Self::KIND, line, format!("invalid number {number:?}: {e}"))
+                })?);
+            }
+        }
+        Ok(Numbers(numbers))
+    }
+}
+
+/// One inclusive range like `3-5` per line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ranges<T>(pub Vec<RangeInclusive<T>>);
+impl<'a, T: FromStr<Err: std::error::Error + Send + Sync + 'static>> Section<'a> for Ranges<T> {
+    const KIND: &'static str = "ranges";
+
+    fn from_block(block: &Block<'a>) -> Result<Self, SectionError> {
+        block
+            .lines()
+            .map(|(line, text)| {
+                parse_inclusive_range(text).map_err(|e| {
+                    block.error(Self::KIND, line, format!("invalid range {text:?}: {e}"))
+                })
+            })
+            .collect::<Result<_, _>>()
+            .map(Ranges)
+    }
+}
+
+/// Rectangular block of chars, e.g. a map to feed into a grid builder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridBlock<'a> {
+    text: &'a str,
+    rows: Vec<&'a str>,
+    size: UVec2,
+}
+impl<'a> GridBlock<'a> {
+    #[must_use]
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    #[must_use]
+    pub fn rows(&self) -> &[&'a str] {
+        &self.rows
+    }
+
+    #[must_use]
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// Every tile with its char in row order.
+    pub fn tiles(&self) -> impl Iterator<Item = (UVec2, char)> + '_ {
+        self.rows.iter().enumerate().flat_map(|(y, row)| {
+            row.chars()
+                .enumerate()
+                .map(move |(x, c)| (UVec2::new(x as _, y as _), c))
+        })
+    }
+}
+impl<'a> Section<'a> for GridBlock<'a> {
+    const KIND: &'static str = "grid";
+
+    fn from_block(block: &Block<'a>) -> Result<Self, SectionError> {
+        let rows: Vec<_> = block.text.lines().collect();
+        let width = rows[0].chars().count();
+        if let Some((line, row)) = block.lines().find(|(_, row)| row.chars().count() != width) {
+            return Err(block.error(
+                Self::KIND,
+                line,
+                format!("row has {} tiles, expected {width}", row.chars().count()),
+            ));
+        }
+        Ok(GridBlock {
+            text: block.text,
+            size: UVec2::new(width as _, rows.len() as _),
+            rows,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+    use tracing_test::traced_test;
+
+    const WAREHOUSE: &str = "\
+#####
+#.O@#
+#####
+
+<^^>
+>vv<
+";
+
+    #[test]
+    #[traced_test]
+    fn grid_and_lines() {
+        let (map, Lines(moves)): (GridBlock, Lines) = parse_sections(WAREHOUSE).unwrap();
+        assert_eq!(map.size(), UVec2::new(5, 3));
+        assert_eq!(map.text(), "#####\n#.O@#\n#####");
+        assert_eq!(
+            map.tiles().find(|(_, c)| *c == '@'),
+            Some((UVec2::new(3, 1), '@'))
+        );
+        assert_eq!(moves, ["<^^>", ">vv<"]);
+    }
+
+    #[test]
+    #[traced_test]
+    fn blocks_lines() {
+        let blocks = blocks("\n\na\nb\r\n\r\n\nc\n\n");
+        assert_eq!(
+            blocks,
+            [
+                Block {
+                    index: 1,
+                    first_line: 3,
+                    text: "a\nb",
+                },
+                Block {
+                    index: 2,
+                    first_line: 7,
+                    text: "c",
+                },
+            ]
+        );
+    }
+
+    #[test_case("3-5\n10-14\n\n1\n5, 8\n11 17 32" => Ok(vec![1, 5, 8, 11, 17, 32]))]
+    #[test_case("3-5\n\n1\n5\nx" => Err("Block 2 (numbers), line 5: invalid number \"x\": invalid digit found in string".to_string()))]
+    #[test_case("3-5" => Err("Block 2 (numbers) is missing, the input has 1 blocks".to_string()))]
+    #[test_case("3-5\n\n1\n\n2" => Err("Unexpected block 3 at line 5, expected 2 blocks".to_string()))]
+    #[traced_test]
+    fn numbers(input: &str) -> Result<Vec<u32>, String> {
+        parse_sections::<(&str, Numbers<u32>)>(input)
+            .map(|(_, Numbers(numbers))| numbers)
+            .map_err(|e| e.to_string())
+    }
+
+    #[test_case("3-5\n10-14\n\n1" => Ok(vec![3..=5, 10..=14]))]
+    #[test_case("\n3-5\n10-x\n\n1" => Err("Block 1 (ranges), line 3: invalid range \"10-x\": invalid digit found in string".to_string()))]
+    #[traced_test]
+    fn ranges(input: &str) -> Result<Vec<RangeInclusive<u32>>, String> {
+        parse_sections::<(Ranges<u32>, &str)>(input)
+            .map(|(Ranges(ranges), _)| ranges)
+            .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    #[traced_test]
+    fn ragged_grid() {
+        let err = parse_sections::<(Lines, GridBlock)>("a\n\n##\n#\n##").unwrap_err();
+        assert_eq!(
+            err,
+            SectionError::InvalidLine {
+                block: 2,
+                kind: "grid",
+                line: 4,
+                reason: "row has 1 tiles, expected 2".to_string(),
+            }
+        );
+    }
+}
@@ -1,7 +1,9 @@
-use glam::{I64Vec2, IVec2, U64Vec2, UVec2};
+use glam::{I64Vec2, I64Vec3, IVec2, IVec3, U64Vec2, U64Vec3, UVec2, UVec3};
 use nom::{bytes::complete::tag, character::complete, sequence::separated_pair, IResult};
 use paste::paste;
 
+use crate::combinators::vector;
+
 macro_rules! impl_parse_vec2 {
     ($name: ident, $vec: ty, $int: ident) => {
         paste! {
@@ -24,3 +26,26 @@ impl_parse_vec2!(ivec2, IVec2, i32);
 impl_parse_vec2!(uvec2, UVec2, u32);
 impl_parse_vec2!(i64vec2, I64Vec2, i64);
 impl_parse_vec2!(u64vec2, U64Vec2, u64);
+
+macro_rules! impl_parse_vec3 {
+    ($name: ident, $vec: ty) => {
+        paste! {
+            #[allow(clippy::missing_errors_doc)]
+            pub fn [<parse_ $name>](input: &str) -> IResult<&str, $vec> {
+                let (input, xyz) = vector(",")(input)?;
+                Ok((input, <$vec>::from_array(xyz)))
+            }
+
+            #[allow(clippy::missing_errors_doc)]
+            pub fn [<parse_ $name _res>](input: &str) -> anyhow::Result<$vec> {
+                let (_, vec) = [<parse_ $name>](input).map_err(|e| e.to_owned())?;
+                Ok(vec)
+            }
+        }
+    };
+}
+
+impl_parse_vec3!(ivec3, IVec3);
+impl_parse_vec3!(uvec3, UVec3);
+impl_parse_vec3!(i64vec3, I64Vec3);
+impl_parse_vec3!(u64vec3, U64Vec3);